@@ -1,4 +1,6 @@
 use crate::*;
+use bevy::sprite::Mesh2dHandle;
+use strum::IntoEnumIterator;
 
 pub struct AssetsPlugin;
 
@@ -9,83 +11,183 @@ impl Plugin for AssetsPlugin {
     }
 }
 
+/// What picks a tile's texture. HP isn't part of it, damage is drawn over
+/// the texture instead, see [`draw_health_pips`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextureKey {
+    Empty(Terrain),
+    Neutral,
+    Occupied {
+        player_tile: PlayerTile,
+        terrain: Terrain,
+        owner: Player,
+        level: usize,
+    },
+}
+
+impl From<TileType> for TextureKey {
+    fn from(tile_type: TileType) -> Self {
+        match tile_type {
+            TileType::Empty(terrain) => TextureKey::Empty(terrain),
+            TileType::Neutral { .. } => TextureKey::Neutral,
+            TileType::Occupied {
+                player_tile,
+                terrain,
+                owner,
+                level,
+                ..
+            } => TextureKey::Occupied {
+                player_tile,
+                terrain,
+                owner,
+                level,
+            },
+        }
+    }
+}
+
+impl TextureKey {
+    fn occupied(player_tile: PlayerTile, owner: Player, level: usize) -> Self {
+        TextureKey::Occupied {
+            player_tile,
+            terrain: Terrain::None,
+            owner,
+            level,
+        }
+    }
+
+    /// The same tile off the mountain. Only level 1 tiles have a mountain
+    /// texture of their own.
+    fn on_flat_ground(self) -> Self {
+        match self {
+            TextureKey::Occupied {
+                player_tile,
+                owner,
+                level,
+                ..
+            } => TextureKey::occupied(player_tile, owner, level),
+            key => key,
+        }
+    }
+}
+
+/// Highest level of each kind of tile there is art for, and what its files
+/// are called. Levels the rules add above it are drawn like the highest.
+const DRAWN_LEVELS: [(PlayerTile, &str, usize); 3] = [
+    (PlayerTile::Tile, "tile", 4),
+    (PlayerTile::Farm, "farm", 3),
+    (PlayerTile::Tower, "tower", 3),
+];
+
 #[derive(Resource, Default)]
 pub struct TileAssets {
-    textures: HashMap<TileType, Handle<Image>>,
+    /// Every texture there is art for.
+    art: HashMap<TextureKey, Handle<Image>>,
+    /// What each tile is drawn with under the match's rules, see
+    /// [`TileAssets::set_rules`].
+    textures: HashMap<TextureKey, Handle<Image>>,
+    /// The shape of a cell on each topology, see [`cell_mesh`].
+    meshes: HashMap<Topology, Mesh2dHandle>,
     pub selector_texture: Handle<Image>,
 }
 
 impl TileAssets {
+    pub fn mesh(&self, topology: Topology) -> Mesh2dHandle {
+        self.meshes[&topology].clone()
+    }
+
+    /// Picks a texture for every level `rules` have, as announced in
+    /// [`ClientEvent::Init`].
+    pub fn set_rules(&mut self, rules: &RuleSet) {
+        let levelled = |key: &TextureKey| {
+            matches!(key, TextureKey::Occupied { player_tile, terrain: Terrain::None, .. }
+                if *player_tile != PlayerTile::Base)
+        };
+        let mut textures = self
+            .art
+            .iter()
+            .filter(|(key, _)| !levelled(key))
+            .map(|(&key, texture)| (key, texture.clone()))
+            .collect::<HashMap<_, _>>();
+
+        for player in Player::iter() {
+            for (player_tile, _, drawn) in DRAWN_LEVELS {
+                for level in 1..=rules.levels.levels(player_tile).len() {
+                    let art = TextureKey::occupied(player_tile, player, level.min(drawn));
+                    textures.insert(
+                        TextureKey::occupied(player_tile, player, level),
+                        self.art[&art].clone(),
+                    );
+                }
+            }
+        }
+
+        self.textures = textures;
+    }
+
     pub fn get(&self, tile_type: TileType) -> Handle<Image> {
+        let key = TextureKey::from(tile_type);
+
         self.textures
-            .get(&tile_type)
-            .unwrap_or(self.textures.get(&TileType::EMPTY).unwrap())
+            .get(&key)
+            .or_else(|| self.textures.get(&key.on_flat_ground()))
+            .unwrap_or(&self.textures[&TextureKey::Empty(Terrain::None)])
             .clone()
     }
 }
 
-fn load_textures(asset_server: Res<AssetServer>, mut assets: ResMut<TileAssets>) {
+fn load_textures(
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut assets: ResMut<TileAssets>,
+) {
     let mut m = HashMap::new();
 
-    m.insert(TileType::EMPTY, asset_server.load("tile-none.png"));
-    m.insert(TileType::WATER, asset_server.load("tile-water.png"));
     m.insert(
-        TileType::Empty(Terrain::Mountain),
+        TextureKey::Empty(Terrain::None),
+        asset_server.load("tile-none.png"),
+    );
+    m.insert(
+        TextureKey::Empty(Terrain::Water),
+        asset_server.load("tile-water.png"),
+    );
+    m.insert(
+        TextureKey::Empty(Terrain::Mountain),
         asset_server.load("tile-mountain.png"),
     );
+    m.insert(TextureKey::Neutral, asset_server.load("tile-neutral.png"));
 
-    // TODO: textures for damaged tiles
-    for player in &[Player::Red, Player::Blue] {
+    for player in Player::iter() {
         m.insert(
-            TileType::Occupied {
-                player_tile: PlayerTile::Base,
-                terrain: Terrain::None,
-                owner: *player,
-                level: 1,
-                hp: 2,
-            },
+            TextureKey::occupied(PlayerTile::Base, player, 1),
             asset_server.load(format!("base-{}.png", player)),
         );
 
         m.insert(
-            TileType::Occupied {
+            TextureKey::Occupied {
                 player_tile: PlayerTile::Tile,
                 terrain: Terrain::Mountain,
-                owner: *player,
+                owner: player,
                 level: 1,
-                hp: 2,
             },
             asset_server.load(format!("tile-mountain-{}.png", player)),
         );
 
-        for i in 1..=4 {
-            m.insert(
-                TileType::Occupied {
-                    player_tile: PlayerTile::Tile,
-                    terrain: Terrain::None,
-                    owner: *player,
-                    level: i,
-                    hp: i,
-                },
-                asset_server.load(format!("tile-{}-{}.png", player, i)),
-            );
-        }
-
-        for i in 1..=3 {
-            m.insert(
-                TileType::Occupied {
-                    player_tile: PlayerTile::Farm,
-                    terrain: Terrain::None,
-                    owner: *player,
-                    level: i,
-                    hp: i,
-                },
-                asset_server.load(format!("farm-{}-{}.png", player, i)),
-            );
+        for (player_tile, name, drawn) in DRAWN_LEVELS {
+            for level in 1..=drawn {
+                m.insert(
+                    TextureKey::occupied(player_tile, player, level),
+                    asset_server.load(format!("{}-{}-{}.png", name, player, level)),
+                );
+            }
         }
     }
 
     assets.selector_texture = asset_server.load("selector.png");
+    assets.meshes = Topology::iter()
+        .map(|topology| (topology, Mesh2dHandle(meshes.add(cell_mesh(topology)))))
+        .collect();
 
-    assets.textures = m;
+    assets.art = m;
+    assets.set_rules(&RuleSet::default());
 }
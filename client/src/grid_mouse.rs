@@ -15,9 +15,9 @@ pub struct GridMouse {
 }
 
 impl GridMouse {
-    /// Returns the position of the mouse as a grid index.
-    pub fn grid_position(&self) -> Vec2 {
-        self.position / TILE_SIZE
+    /// Returns the cell under the mouse on a board laid out by `topology`.
+    pub fn grid_position(&self, topology: Topology) -> GridPos {
+        topology.grid_position(self.position)
     }
 }
 
@@ -26,9 +26,9 @@ fn update_mouse_position(
     camera_query: Query<(&Camera, &GlobalTransform)>,
     q_windows: Query<&Window, With<PrimaryWindow>>,
 ) {
-    let Some(rectified_position) = get_rectified_mouse_position(camera_query, q_windows) else {
+    let Some(position) = get_world_mouse_position(camera_query, q_windows) else {
         return;
     };
 
-    mouse.position = rectified_position;
+    mouse.position = position;
 }
@@ -1,16 +1,40 @@
+use std::time::Duration;
+
 use crate::*;
 
 pub struct HUDPlugin;
 
 impl Plugin for HUDPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(ClientState::Terrain), setup_hud)
-            .add_systems(OnExit(ClientState::Game), remove_hud);
+        app.add_event::<Toast>()
+            .init_resource::<Stockpiles>()
+            .insert_resource(ToastTimer(Timer::from_seconds(
+                TOAST_DURATION_SECS,
+                TimerMode::Once,
+            )))
+            .add_systems(OnEnter(ClientState::Terrain), setup_hud)
+            .add_systems(OnEnter(ClientState::Results), remove_hud)
+            .add_systems(
+                Update,
+                (
+                    (show_toast, hide_toast).chain(),
+                    update_rules_text.run_if(resource_changed::<RuleSet>()),
+                    (
+                        setup_resource_text.run_if(resource_exists_and_changed::<MapConfig>()),
+                        update_resource_text.run_if(
+                            resource_changed::<Stockpiles>()
+                                .or_else(resource_exists_and_changed::<MapConfig>()),
+                        ),
+                    )
+                        .chain(),
+                )
+                    .run_if(in_state(ClientState::Game).or_else(in_state(ClientState::Terrain))),
+            );
     }
 }
 
 #[derive(Component, Clone, Debug)]
-pub struct FarmText;
+pub struct ResourceText(pub Player);
 
 #[derive(Component, Clone, Debug)]
 pub struct TurnText;
@@ -18,13 +42,41 @@ pub struct TurnText;
 #[derive(Component, Clone, Debug)]
 pub struct PlacementModeText;
 
-fn setup_hud(mut commands: Commands) {
-    [
-        ("red", SCOREBOARD_TEXT_PADDING),
-        ("blue", SCOREBOARD_TEXT_PADDING_2),
-    ]
-    .iter()
-    .for_each(|&(m, margin)| {
+#[derive(Component, Clone, Debug)]
+pub struct ToastText;
+
+/// Summary of the rules the match is played by, see [`rules_text`].
+#[derive(Component, Clone, Debug)]
+pub struct RulesText;
+
+/// Short lived message at the bottom of the screen, e.g. why the server
+/// rejected the last click.
+#[derive(Event, Clone, Debug)]
+pub struct Toast(pub String);
+
+#[derive(Resource)]
+struct ToastTimer(Timer);
+
+/// Each player's stockpile and income, as last announced by the server. Kept
+/// apart from the text so values sent together with `Init` aren't lost.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct Stockpiles {
+    pub balance: Vec<Option<usize>>,
+    pub income: Vec<Option<usize>>,
+}
+
+/// One stockpile counter per seat, respawned whenever the server announces a
+/// map.
+fn setup_resource_text(
+    mut commands: Commands,
+    config: Res<MapConfig>,
+    existing: Query<Entity, With<ResourceText>>,
+) {
+    for ent in &existing {
+        commands.entity(ent).despawn();
+    }
+
+    config.players().enumerate().for_each(|(i, player)| {
         let text_style = TextStyle {
             font_size: SCOREBOARD_FONT_SIZE,
             color: SCORE_COLOR,
@@ -33,7 +85,7 @@ fn setup_hud(mut commands: Commands) {
         commands.spawn((
             TextBundle::from_sections([
                 TextSection {
-                    value: format!("Farms {m}: "),
+                    value: format!("Resources {player}: "),
                     style: text_style.clone(),
                 },
                 TextSection {
@@ -43,14 +95,27 @@ fn setup_hud(mut commands: Commands) {
             ])
             .with_style(Style {
                 position_type: PositionType::Absolute,
-                top: margin,
+                top: Val::Px(SCOREBOARD_LINE_HEIGHT * i as f32 + 5.0),
                 left: SCOREBOARD_TEXT_PADDING,
                 ..default()
             }),
-            FarmText,
+            ResourceText(player),
         ));
     });
+}
 
+fn update_resource_text(stockpiles: Res<Stockpiles>, mut query: Query<(&ResourceText, &mut Text)>) {
+    for (ResourceText(player), mut text) in query.iter_mut() {
+        let i = *player as usize;
+        text.sections[1].value = match (stockpiles.balance.get(i), stockpiles.income.get(i)) {
+            (Some(Some(balance)), Some(Some(income))) => format!("{} (+{})", balance, income),
+            // Hidden by fog of war
+            _ => "?".to_string(),
+        };
+    }
+}
+
+fn setup_hud(mut commands: Commands) {
     // Create turn text
     commands.spawn((
         TextBundle::from_sections([
@@ -70,6 +135,24 @@ fn setup_hud(mut commands: Commands) {
                     ..default()
                 },
             },
+            // Countdown, left empty in untimed matches
+            TextSection {
+                value: String::new(),
+                style: TextStyle {
+                    font_size: SCOREBOARD_FONT_SIZE,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            },
+            // Action points left, empty until the game phase
+            TextSection {
+                value: String::new(),
+                style: TextStyle {
+                    font_size: SCOREBOARD_FONT_SIZE,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            },
         ])
         .with_style(Style {
             position_type: PositionType::Absolute,
@@ -108,22 +191,151 @@ fn setup_hud(mut commands: Commands) {
         }),
         PlacementModeText,
     ));
+
+    // Toast text, hidden until something is shown
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: TOAST_FONT_SIZE,
+                color: TEXT_COLOR,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: SCOREBOARD_TEXT_PADDING,
+            left: SCOREBOARD_TEXT_PADDING,
+            ..default()
+        }),
+        ToastText,
+    ));
+
+    // Rules text, filled in once the server announces them
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: RULES_FONT_SIZE,
+                color: TEXT_COLOR,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: SCOREBOARD_TEXT_PADDING,
+            right: SCOREBOARD_TEXT_PADDING,
+            ..default()
+        }),
+        RulesText,
+    ));
+}
+
+/// Time left as shown next to the turn, e.g. ` 1:05`. Seconds are rounded
+/// up so the clock reads `0:00` only once time is up.
+pub fn clock_text(remaining: Duration) -> String {
+    let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+    format!(" {}:{:02}", secs / 60, secs % 60)
+}
+
+/// Action points left as shown after the turn, e.g. ` (2 AP)`.
+pub fn action_points_text(remaining: usize) -> String {
+    format!(" ({} AP)", remaining)
+}
+
+/// The rules as listed in the corner of the screen, one topic per line.
+pub fn rules_text(rules: &RuleSet) -> String {
+    let upgrades = |player_tile| {
+        rules
+            .levels
+            .levels(player_tile)
+            .iter()
+            .skip(1)
+            .map(|level| level.upgrade_cost.to_string())
+            .collect::<Vec<_>>()
+            .join("/")
+    };
+    let costs = &rules.costs;
+
+    [
+        format!("Rules: {}", rules.name),
+        format!(
+            "Attack {} per tile, farm {}, tower {}, repair {} per HP",
+            costs.attack, costs.make_farm, costs.make_tower, costs.repair
+        ),
+        format!(
+            "Upgrades: tile {}, farm {}, tower {}",
+            upgrades(PlayerTile::Tile),
+            upgrades(PlayerTile::Farm),
+            upgrades(PlayerTile::Tower)
+        ),
+        format!(
+            "Income {} + {} per farm level, {} AP + 1 per {} farm levels",
+            rules.base_income, rules.farm_income, rules.action_points, rules.farms_per_action_point
+        ),
+        format!(
+            "Bases {} HP, 1 HP back every {} turns",
+            rules.base_hp, rules.base_regen_turns
+        ),
+        format!(
+            "Towers hit for {} within {}, 1 further per level",
+            rules.tower_damage, rules.tower_range
+        ),
+        format!(
+            "Terrain: {} mountains, {} water each",
+            rules.mountain_budget, rules.water_budget
+        ),
+    ]
+    .join("\n")
+}
+
+fn update_rules_text(rules: Res<RuleSet>, mut query: Query<&mut Text, With<RulesText>>) {
+    for mut text in query.iter_mut() {
+        text.sections[0].value = rules_text(&rules);
+    }
+}
+
+fn show_toast(
+    mut toasts: EventReader<Toast>,
+    mut query: Query<&mut Text, With<ToastText>>,
+    mut timer: ResMut<ToastTimer>,
+) {
+    let Some(Toast(message)) = toasts.read().last() else {
+        return;
+    };
+
+    for mut text in query.iter_mut() {
+        text.sections[0].value = message.clone();
+    }
+    timer.0.reset();
+}
+
+fn hide_toast(
+    time: Res<Time>,
+    mut query: Query<&mut Text, With<ToastText>>,
+    mut timer: ResMut<ToastTimer>,
+) {
+    if timer.0.tick(time.delta()).just_finished() {
+        for mut text in query.iter_mut() {
+            text.sections[0].value.clear();
+        }
+    }
 }
 
 fn remove_hud(
     mut commands: Commands,
-    query: Query<Entity, Or<(With<PlacementModeText>, With<TurnText>, With<FarmText>)>>,
+    query: Query<
+        Entity,
+        Or<(
+            With<PlacementModeText>,
+            With<TurnText>,
+            With<ResourceText>,
+            With<ToastText>,
+            With<RulesText>,
+        )>,
+    >,
 ) {
     for ent in &query {
         commands.entity(ent).despawn();
     }
 }
-
-// fn set_turn(mut query: Query<&mut Text, With<TurnText>>, turn: Res<TurnCounter>) {
-//     for mut text in &mut query {
-//         (text.sections[1].value, text.sections[1].style.color) = match turn.player() {
-//             Player::Red => ("Red".to_string(), Color::rgb(1.0, 0.0, 0.0)),
-//             Player::Blue => ("Blue".to_string(), Color::rgb(0.0, 0.0, 1.0)),
-//         };
-//     }
-// }
@@ -3,7 +3,8 @@
 use assets::{AssetsPlugin, TileAssets};
 pub use bevy::prelude::*;
 use bevy::{
-    utils::HashMap,
+    sprite::MaterialMesh2dBundle,
+    utils::{HashMap, HashSet},
     window::{close_on_esc, PrimaryWindow},
 };
 use bevy_renet::{
@@ -16,18 +17,23 @@ use bevy_renet::{
 };
 use camera::CameraPlugin;
 use grid_mouse::*;
-use hud::{FarmText, HUDPlugin, PlacementModeText, TurnText};
-use menu::MenuPlugin;
+use hud::{
+    action_points_text, clock_text, HUDPlugin, PlacementModeText, ResourceText, Stockpiles, Toast,
+    TurnText,
+};
+use menu::{MenuPlugin, WinCounter};
+use results::{MatchResult, ResultsPlugin};
 use std::{net::UdpSocket, time::SystemTime};
 use store::*;
 use tiles::*;
-use utils::{get_rectified_mouse_position, get_vec_from_index};
+use utils::{fog_tint, get_world_mouse_position, player_color};
 
 mod assets;
 mod camera;
 mod grid_mouse;
 mod hud;
 mod menu;
+mod results;
 mod tiles;
 mod utils;
 
@@ -43,6 +49,7 @@ fn main() {
         GridMousePlugin,
         HUDPlugin,
         MenuPlugin,
+        ResultsPlugin,
         AssetsPlugin,
         RenetClientPlugin,
         NetcodeClientPlugin,
@@ -53,10 +60,13 @@ fn main() {
         tiles: HashMap::default(),
         selector: None,
     });
+    app.init_resource::<SeenTiles>();
+    app.init_resource::<Topology>();
+    app.init_resource::<RuleSet>();
 
     app.add_systems(OnEnter(ClientState::Lobby), insert_client)
         .add_systems(OnEnter(ClientState::Terrain), setup.after(menu::cleanup))
-        .add_systems(OnExit(ClientState::Game), cleanup)
+        .add_systems(OnEnter(ClientState::Results), cleanup)
         .add_systems(
             Update,
             (
@@ -80,6 +90,11 @@ pub struct EntityTable {
     pub selector: Option<Entity>,
 }
 
+/// Every tile this player has had in view, so fog can tell tiles seen before
+/// from ones never seen.
+#[derive(Resource, Default)]
+pub struct SeenTiles(pub HashSet<GridPos>);
+
 fn insert_client(world: &mut World) {
     let (client, transport) = new_renet_client();
     world.insert_resource(client);
@@ -122,27 +137,20 @@ fn new_renet_client() -> (RenetClient, NetcodeClientTransport) {
     (client, transport)
 }
 
-fn setup(mut commands: Commands, mut entity_table: ResMut<EntityTable>, assets: Res<TileAssets>) {
-    (0..(MAP_HEIGHT * MAP_WIDTH * 4.0) as usize).for_each(|index| {
-        entity_table.tiles.insert(
-            index,
-            commands
-                .spawn(TileBundle::blank(get_vec_from_index(index), &assets))
-                .id(),
-        );
-    });
-
-    // Spawn selector
+fn setup(
+    mut commands: Commands,
+    mut entity_table: ResMut<EntityTable>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    assets: Res<TileAssets>,
+) {
+    // Spawn selector, shaped to the board once the server announces it
     entity_table.selector = Some(
         commands
             .spawn((
                 Selector,
-                SpriteBundle {
-                    sprite: Sprite {
-                        custom_size: Some(Vec2::splat(TILE_SIZE)),
-                        ..default()
-                    },
-                    texture: assets.selector_texture.clone(),
+                MaterialMesh2dBundle {
+                    mesh: assets.mesh(Topology::default()),
+                    material: materials.add(assets.selector_texture.clone().into()),
                     visibility: Visibility::Hidden,
                     ..default()
                 },
@@ -151,10 +159,46 @@ fn setup(mut commands: Commands, mut entity_table: ResMut<EntityTable>, assets:
     );
 }
 
-fn cleanup(mut commands: Commands, mut entities: Query<Entity, With<Selector>>) {
+fn cleanup(
+    mut commands: Commands,
+    mut entities: Query<Entity, Or<(With<Selector>, With<Position>)>>,
+    mut entity_table: ResMut<EntityTable>,
+) {
     for e in entities.iter_mut() {
         commands.entity(e).despawn_recursive();
     }
+    entity_table.tiles.clear();
+}
+
+/// Spawns one tile per cell of the board announced by the server, replacing
+/// any tiles left over from a previous `Init`.
+fn spawn_tiles(
+    commands: &mut Commands,
+    entity_table: &mut EntityTable,
+    materials: &mut Assets<ColorMaterial>,
+    grid: &TileGrid,
+    rules: &RuleSet,
+    assets: &TileAssets,
+) {
+    for (_, e) in entity_table.tiles.drain() {
+        commands.entity(e).despawn_recursive();
+    }
+    if let Some(e) = entity_table.selector {
+        commands.entity(e).insert(assets.mesh(grid.topology));
+    }
+
+    for (index, &tile) in grid.get_tiles().enumerate() {
+        let entity = commands
+            .spawn(TileBundle::new(
+                grid.get_pos_from_index(index),
+                grid.topology,
+                assets,
+                materials.add(assets.get(tile).into()),
+            ))
+            .id();
+        draw_health_pips(commands, entity, tile, rules);
+        entity_table.tiles.insert(index, entity);
+    }
 }
 
 fn noop<T>(_: T) {}
@@ -162,29 +206,23 @@ fn noop<T>(_: T) {}
 fn register_event(
     (mouse, mut buttons): (Res<GridMouse>, ResMut<Input<MouseButton>>),
     keys: Res<Input<KeyCode>>,
+    topology: Res<Topology>,
     (mut client, transport): (ResMut<RenetClient>, Res<NetcodeClientTransport>),
     state: Res<State<ClientState>>,
 ) -> Option<()> {
-    const INPUTS: [GameInput; 6] = [
-        GameInput::Mouse(MouseButton::Left),
-        GameInput::Mouse(MouseButton::Right),
-        GameInput::Keyboard(KeyCode::Space),
-        GameInput::Keyboard(KeyCode::M),
-        GameInput::Keyboard(KeyCode::W),
-        GameInput::Keyboard(KeyCode::Return),
-    ];
     let keys = keys.get_just_pressed().map(|k| GameInput::Keyboard(*k));
     let input = buttons
         .get_just_pressed()
         .map(|b| GameInput::Mouse(*b))
         .chain(keys)
-        .find(|x| INPUTS.contains(x))?;
-    info!("{:?}", mouse.grid_position());
+        .find(|x| GameInput::ALL.contains(x))?;
+    let position = mouse.grid_position(*topology);
+    info!("{:?}", position);
     client.send_message(
         DefaultChannel::ReliableOrdered,
         bincode::serialize(&TileEvent::from_input(
             transport.client_id(),
-            mouse.grid_position(),
+            position,
             input,
             state.get(),
         ))
@@ -198,20 +236,17 @@ fn register_event(
 #[allow(clippy::too_many_arguments)]
 fn receive_events_from_server(
     mut client: ResMut<RenetClient>,
-    mut tiles: Query<(&Position, &mut Handle<Image>)>,
-    mut farms_text: Query<
-        &mut Text,
-        (
-            With<FarmText>,
-            Without<TurnText>,
-            Without<PlacementModeText>,
-        ),
-    >,
+    tiles: Query<(Entity, &Position, &Handle<ColorMaterial>)>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut stockpiles: ResMut<Stockpiles>,
+    mut seen: ResMut<SeenTiles>,
+    mut topology: ResMut<Topology>,
+    mut rules: ResMut<RuleSet>,
     mut turn_text: Query<
         &mut Text,
         (
             With<TurnText>,
-            Without<FarmText>,
+            Without<ResourceText>,
             Without<PlacementModeText>,
         ),
     >,
@@ -219,39 +254,56 @@ fn receive_events_from_server(
         &mut Text,
         (
             With<PlacementModeText>,
-            Without<FarmText>,
+            Without<ResourceText>,
             Without<TurnText>,
         ),
     >,
     mut commands: Commands,
+    mut toasts: EventWriter<Toast>,
+    mut win_counter: ResMut<WinCounter>,
     mut next_state: ResMut<NextState<ClientState>>,
-    entity_table: Res<EntityTable>,
-    assets: Res<TileAssets>,
+    mut entity_table: ResMut<EntityTable>,
+    mut assets: ResMut<TileAssets>,
 ) {
     while let Some(message) = client.receive_message(DefaultChannel::ReliableOrdered) {
         let event: ClientEvent = bincode::deserialize(&message).unwrap();
         info!("{:#?}", event);
 
         match event {
-            ClientEvent::Init(grid) => {
-                for (pos, mut image) in tiles.iter_mut() {
-                    *image = assets.get(grid.get_tile(pos.as_grid_index()));
-                }
+            ClientEvent::Init(config, grid, rule_set) => {
+                *topology = grid.topology;
+                *rules = *rule_set;
+                assets.set_rules(&rules);
+                spawn_tiles(
+                    &mut commands,
+                    &mut entity_table,
+                    &mut materials,
+                    &grid,
+                    &rules,
+                    &assets,
+                );
+                *stockpiles = Stockpiles::default();
+                seen.0.clear();
+                commands.insert_resource(config);
             }
             ClientEvent::TileChanges(changes) => changes.iter().for_each(|change| {
-                if let Some((_, mut image)) = tiles
-                    .iter_mut()
-                    .find(|(pos, _)| pos.as_grid_index() == change.position)
+                if let Some((entity, _, material)) = tiles
+                    .iter()
+                    .find(|(_, pos, _)| topology.grid_position(pos.0) == change.position)
                 {
-                    *image = assets.get(change.tile);
+                    if let Some(material) = materials.get_mut(material) {
+                        material.texture = Some(assets.get(change.tile));
+                    }
+                    draw_health_pips(&mut commands, entity, change.tile, &rules);
                 }
             }),
             ClientEvent::Select(position) => {
                 if let Some(e) = entity_table.selector {
                     commands.entity(e).insert((
                         Visibility::Visible,
+                        // Above the tile, below its health pips
                         Transform::from_translation(
-                            (position * TILE_SIZE + Vec2::splat(TILE_SIZE / 2.0)).extend(0.0),
+                            topology.world_position(position).0.extend(0.5),
                         ),
                     ));
                 }
@@ -261,22 +313,46 @@ fn receive_events_from_server(
                     commands.entity(e).insert(Visibility::Hidden);
                 }
             }
-            ClientEvent::Farms(farms) => {
-                farms_text.iter_mut().enumerate().for_each(|(i, mut t)| {
-                    t.sections[1].value = format!("{}", farms[i]);
-                });
+            ClientEvent::Resources { balance, income } => {
+                *stockpiles = Stockpiles { balance, income }
             }
             ClientEvent::Turn(player) => turn_text.iter_mut().for_each(|mut t| {
                 t.sections[1].value = format!("{}", player);
-                t.sections[1].style.color = match player {
-                    Player::Red => Color::RED,
-                    Player::Blue => Color::BLUE,
-                };
+                t.sections[1].style.color = player_color(player);
+                // Only sent to the player on turn when there is fog
+                t.sections[3].value.clear();
+            }),
+            ClientEvent::Vision(vision) => {
+                for (_, position, material) in tiles.iter() {
+                    let position = topology.grid_position(position.0);
+                    let visible = vision.contains(position);
+                    if visible {
+                        seen.0.insert(position);
+                    }
+                    if let Some(material) = materials.get_mut(material) {
+                        material.color = fog_tint(visible, seen.0.contains(&position));
+                    }
+                }
+            }
+            ClientEvent::Clock { remaining, .. } => turn_text.iter_mut().for_each(|mut t| {
+                t.sections[2].value = clock_text(remaining);
+            }),
+            ClientEvent::ActionPoints(remaining) => turn_text.iter_mut().for_each(|mut t| {
+                t.sections[3].value = action_points_text(remaining);
             }),
             ClientEvent::TerrainMode(terrain) => terrain_text.iter_mut().for_each(|mut t| {
                 t.sections[1].value = format!("{}", terrain);
             }),
             ClientEvent::GamePhase(state) => next_state.set(state),
+            ClientEvent::ActionRejected(reason) => toasts.send(Toast(reason.to_string())),
+            ClientEvent::Eliminated { player, reason } => {
+                toasts.send(Toast(format!("{} is out ({})", player, reason)))
+            }
+            ClientEvent::GameOver { winner, reason } => {
+                win_counter.increment(winner, 1);
+                commands.insert_resource(MatchResult { winner, reason });
+                next_state.set(ClientState::Results);
+            }
         }
     }
 }
@@ -1,4 +1,6 @@
 use crate::*;
+use itertools::Itertools;
+use strum::IntoEnumIterator;
 
 pub struct MenuPlugin;
 
@@ -16,7 +18,7 @@ impl Plugin for MenuPlugin {
 
 #[derive(Resource, Default)]
 pub struct WinCounter {
-    wins: [usize; 2],
+    wins: [usize; MAX_PLAYERS],
 }
 
 impl WinCounter {
@@ -63,7 +65,7 @@ fn setup_menu(mut commands: Commands) {
     commands.spawn((
         TextBundle {
             text: Text::from_section(
-                "Player Wins: 0",
+                "Wins",
                 TextStyle {
                     font_size: 40.0,
                     color: Color::rgb(0.9, 0.9, 0.9),
@@ -116,6 +118,11 @@ fn update_player_wins(
     mut query: Query<&mut Text, With<PlayerWinsText>>,
 ) {
     for mut text in query.iter_mut() {
-        text.sections[0].value = format!("Player Wins: {}", player_wins.get(Player::Red));
+        text.sections[0].value = format!(
+            "Wins: {}",
+            Player::iter()
+                .map(|p| format!("{} {}", p, player_wins.get(p)))
+                .join(", ")
+        );
     }
 }
@@ -0,0 +1,102 @@
+use crate::*;
+
+pub struct ResultsPlugin;
+
+impl Plugin for ResultsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(ClientState::Results), setup_results)
+            .add_systems(OnExit(ClientState::Results), cleanup)
+            .add_systems(
+                Update,
+                results_manager.run_if(in_state(ClientState::Results)),
+            );
+    }
+}
+
+/// Outcome of the last match, set when the server announces `GameOver`.
+#[derive(Resource, Debug, Clone)]
+pub struct MatchResult {
+    pub winner: Player,
+    pub reason: GameOverReason,
+}
+
+#[derive(Component)]
+pub struct ResultsScreen;
+
+fn setup_results(mut commands: Commands, result: Res<MatchResult>) {
+    let title = commands
+        .spawn(TextBundle {
+            text: Text::from_section(
+                format!("{} wins! ({})", result.winner, result.reason),
+                TextStyle {
+                    font_size: 40.0,
+                    color: player_color(result.winner),
+                    ..default()
+                },
+            ),
+            ..default()
+        })
+        .id();
+
+    let text = commands
+        .spawn(TextBundle {
+            text: Text::from_section(
+                "Back to menu",
+                TextStyle {
+                    font_size: 40.0,
+                    color: Color::rgb(0.9, 0.9, 0.9),
+                    ..default()
+                },
+            ),
+            ..default()
+        })
+        .id();
+
+    let button = commands
+        .spawn(ButtonBundle {
+            style: Style {
+                margin: UiRect::all(Val::Px(20.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .insert_children(0, &[text])
+        .id();
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    min_width: Val::Percent(100.0),
+                    min_height: Val::Percent(100.0),
+                    ..default()
+                },
+                ..default()
+            },
+            ResultsScreen,
+        ))
+        .insert_children(0, &[title, button]);
+}
+
+fn cleanup(mut commands: Commands, entities: Query<Entity, With<ResultsScreen>>) {
+    for ent in entities.iter() {
+        commands.entity(ent).despawn_recursive()
+    }
+}
+
+fn results_manager(
+    interaction: Query<(&Interaction, &Button), Changed<Interaction>>,
+    mut state: ResMut<NextState<ClientState>>,
+) {
+    interaction
+        .iter()
+        .filter(|(i, _)| matches!(i, Interaction::Pressed))
+        .for_each(|_| {
+            state.set(ClientState::Menu);
+        })
+}
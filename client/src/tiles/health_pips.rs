@@ -0,0 +1,42 @@
+use crate::*;
+
+/// One HP of a damaged tile, drawn as a child of the tile.
+#[derive(Component, Clone, Debug, PartialEq, Default)]
+pub struct HealthPip;
+
+/// Redraws the pips over the `entity` showing `tile`. Undamaged tiles get
+/// none, damaged ones a row along their bottom edge with the HP lost, as far
+/// as `rules` go, greyed out.
+pub fn draw_health_pips(commands: &mut Commands, entity: Entity, tile: TileType, rules: &RuleSet) {
+    commands.entity(entity).despawn_descendants();
+
+    let health = tile.hp().zip(rules.max_hp(tile));
+    let Some((hp, max)) = health.filter(|&(hp, max)| hp < max) else {
+        return;
+    };
+
+    let step = HEALTH_PIP_SIZE * 1.5;
+    let left = -((max - 1) as f32) * step / 2.0;
+    let bottom = -TILE_SIZE / 2.0 + HEALTH_PIP_SIZE;
+
+    commands.entity(entity).with_children(|parent| {
+        for i in 0..max {
+            parent.spawn((
+                HealthPip,
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: if i < hp {
+                            HEALTH_PIP_COLOR
+                        } else {
+                            LOST_HEALTH_PIP_COLOR
+                        },
+                        custom_size: Some(Vec2::splat(HEALTH_PIP_SIZE)),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(left + i as f32 * step, bottom, 1.0),
+                    ..default()
+                },
+            ));
+        }
+    });
+}
@@ -1,3 +1,5 @@
+pub use health_pips::*;
 pub use tile_bundle::*;
 
+pub mod health_pips;
 pub mod tile_bundle;
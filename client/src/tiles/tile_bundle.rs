@@ -1,28 +1,75 @@
+use bevy::{
+    render::{mesh::Indices, render_resource::PrimitiveTopology},
+    sprite::MaterialMesh2dBundle,
+};
+
 use crate::*;
 
 #[derive(Bundle, Default)]
 pub struct TileBundle {
     position: Position,
-    sprite_bundle: SpriteBundle,
+    mesh_bundle: MaterialMesh2dBundle<ColorMaterial>,
 }
 
 impl TileBundle {
-    pub fn blank(Vec2 { x, y }: Vec2, assets: &TileAssets) -> Self {
-        let position =
-            Position(Vec2::new(x - MAP_WIDTH, y - MAP_HEIGHT) * TILE_SIZE + TILE_SIZE / 2.0);
+    /// A tile at grid coordinates `index`, placed and shaped as `topology`
+    /// lays out the board. Every tile has a `material` of its own, its
+    /// texture and fog tint change on their own.
+    pub fn new(
+        index: GridPos,
+        topology: Topology,
+        assets: &TileAssets,
+        material: Handle<ColorMaterial>,
+    ) -> Self {
+        let position = topology.world_position(index);
         let transform = position.clone().into();
 
         Self {
             position,
-            sprite_bundle: SpriteBundle {
+            mesh_bundle: MaterialMesh2dBundle {
                 transform,
-                sprite: Sprite {
-                    custom_size: Some(Vec2::splat(TILE_SIZE)),
-                    ..default()
-                },
-                texture: assets.get(TileType::EMPTY),
+                mesh: assets.mesh(topology),
+                material,
                 ..default()
             },
         }
     }
 }
+
+/// A cell of `topology` as a fan of triangles round its centre, see
+/// [`Topology::outline`]. Textures are stretched over the cell's bounding
+/// box, the parts of them outside a hex cell are cut off.
+pub fn cell_mesh(topology: Topology) -> Mesh {
+    let outline = topology.outline();
+    let size = outline
+        .iter()
+        .fold(Vec2::ZERO, |size, corner| size.max(corner.abs() * 2.0));
+
+    let positions = std::iter::once(Vec2::ZERO)
+        .chain(outline.iter().copied())
+        .collect::<Vec<_>>();
+    let uvs = positions
+        .iter()
+        .map(|p| [0.5 + p.x / size.x, 0.5 - p.y / size.y])
+        .collect::<Vec<_>>();
+    let corners = outline.len() as u32;
+    let indices = (1..=corners)
+        .flat_map(|i| [0, i, i % corners + 1])
+        .collect::<Vec<_>>();
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        positions
+            .iter()
+            .map(|p| [p.x, p.y, 0.0])
+            .collect::<Vec<_>>(),
+    );
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_NORMAL,
+        vec![[0.0, 0.0, 1.0]; positions.len()],
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.set_indices(Some(Indices::U32(indices)));
+    mesh
+}
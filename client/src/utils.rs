@@ -1,23 +1,31 @@
-use std::usize;
-
 use crate::*;
 use bevy::window::PrimaryWindow;
 
-pub fn get_rectified_mouse_position(
+pub fn player_color(player: Player) -> Color {
+    match player {
+        Player::Red => Color::RED,
+        Player::Blue => Color::BLUE,
+        Player::Green => Color::GREEN,
+        Player::Yellow => Color::YELLOW,
+    }
+}
+
+/// How a tile is shaded under fog of war: untouched while in view, dimmed
+/// while it shows what was last seen there, dark if never seen at all.
+pub fn fog_tint(visible: bool, seen: bool) -> Color {
+    match (visible, seen) {
+        (true, _) => Color::WHITE,
+        (false, true) => LAST_SEEN_TINT,
+        (false, false) => UNKNOWN_TINT,
+    }
+}
+
+pub fn get_world_mouse_position(
     camera_query: Query<(&Camera, &GlobalTransform)>,
     q_windows: Query<&Window, With<PrimaryWindow>>,
 ) -> Option<Vec2> {
     let (camera, camera_transform) = camera_query.single();
 
     let viewport_position = q_windows.single().cursor_position()?;
-    let position = camera.viewport_to_world_2d(camera_transform, viewport_position)?;
-
-    Some((position / TILE_SIZE).floor() * TILE_SIZE)
+    camera.viewport_to_world_2d(camera_transform, viewport_position)
 }
-
-pub fn get_vec_from_index(index: usize) -> Vec2 {
-    Vec2::new(
-        (index % (MAP_WIDTH as usize * 2)) as f32,
-        (index / (MAP_WIDTH as usize * 2)) as f32,
-    )
-}
\ No newline at end of file
@@ -1,18 +1,32 @@
 use std::{
-    collections::HashMap, net::{SocketAddr, UdpSocket}, thread, time::{Duration, Instant, SystemTime}
+    collections::HashMap, fs::File, io::BufWriter, net::{SocketAddr, UdpSocket}, path::Path, process, thread, time::{Duration, Instant, SystemTime}
 };
 
-use bincode::ErrorKind;
-use log::{info, warn};
+use log::{error, info, warn};
 use renet::{
     transport::{NetcodeServerTransport, ServerAuthentication, ServerConfig}, ConnectionConfig, DefaultChannel, RenetServer, ServerEvent
 };
-use store::{ClientEvent, GameState, StartGame, TileEvent};
+use options::ServerOptions;
+use store::{
+    choose_event, generate_terrain, AiLevel, ClientEvent, FogOfWar, GameAction, GameState,
+    MapConfig, Mode, Player, ReplayEntry, ReplayWriter, RuleError, RuleSet, StartGame, TileEvent,
+    Vision, DEFAULT_MAP_HEIGHT, DEFAULT_MAP_WIDTH,
+};
+
+mod options;
 
 const PROTOCOL_ID: u64 = 7;
+/// Client ids handed to bots, counting down so they never clash with the
+/// timestamps real clients use.
+const BOT_CLIENT_ID: u64 = u64::MAX;
+/// Pause between bot moves so humans can follow them.
+const BOT_MOVE_DELAY: Duration = Duration::from_millis(500);
+/// How often clients hear how much time the player on turn has left.
+const CLOCK_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
 
 fn main() {
     env_logger::init();
+    let options = ServerOptions::from_args();
 
     let public_addr: SocketAddr = format!("0.0.0.0:{}", "5000").parse().unwrap();
     let connection_config = ConnectionConfig::default();
@@ -30,10 +44,104 @@ fn main() {
 
     let mut transport = NetcodeServerTransport::new(server_config, socket).unwrap();
 
-    let mut game_state = GameState::default();
+    let mut game_state = match &options.load {
+        Some(path) => {
+            let mut state = GameState::load_from_path(path).unwrap_or_else(|err| {
+                error!("Failed to load {}: {:#}", path.display(), err);
+                process::exit(1)
+            });
+            // Client ids only live as long as a connection, seats are handed
+            // out again as players reconnect
+            state.id_to_player.clear();
+            if options.rules.is_some() {
+                warn!("Ignoring --rules, {} keeps its own", path.display());
+            }
+            if options.mode != Mode::default() {
+                warn!("Ignoring --mode, {} keeps its own", path.display());
+            }
+            info!(
+                "Resuming {}, waiting for {} players",
+                path.display(),
+                state.map_config.player_count()
+            );
+            state
+        }
+        None => {
+            let mut map_config = match &options.map {
+                Some(path) => MapConfig::load_from_path(path).unwrap_or_else(|err| {
+                    error!("Failed to load {}: {:#}", path.display(), err);
+                    process::exit(1)
+                }),
+                None => MapConfig::with_players(
+                    DEFAULT_MAP_WIDTH,
+                    DEFAULT_MAP_HEIGHT,
+                    options.players,
+                ),
+            };
+
+            if let Some(topology) = options.topology {
+                info!("Playing on a {} board", topology);
+                map_config.topology = topology;
+            }
+
+            let map_config = match options.seed {
+                Some(seed) => {
+                    info!("Generating terrain with seed {}", seed);
+                    generate_terrain(&map_config, seed)
+                }
+                None => map_config,
+            };
+
+            let rules = match &options.rules {
+                Some(path) => RuleSet::load_from_path(path).unwrap_or_else(|err| {
+                    error!("Failed to load {}: {:#}", path.display(), err);
+                    process::exit(1)
+                }),
+                None => RuleSet::default(),
+            };
+            info!("Playing by the {} rules", rules.name);
+
+            let mut state = GameState::with_rules(map_config, rules);
+            info!("Playing {}", options.mode);
+            state.set_mode(options.mode);
+            if options.skip_placement || options.seed.is_some() {
+                state.skip_terrain_placement();
+            }
+            state
+        }
+    };
+
+    if let Some(range) = options.fog {
+        info!("Playing with fog of war, vision range {}", range);
+        game_state.fog = Some(FogOfWar { range });
+    }
+
+    if let Some(control) = options.time_control {
+        info!("Timing the match with {:?}", control);
+        game_state.set_time_control(control);
+    }
+
+    let player_count = game_state.map_config.player_count();
+    if options.ai.len() >= player_count {
+        error!("At most {} bots fit on this map", player_count - 1);
+        process::exit(1);
+    }
+
+    // Bots take the last seats, humans fill the rest as they connect
+    let mut bots: HashMap<Player, AiLevel> = HashMap::new();
+    for (i, &level) in options.ai.iter().enumerate() {
+        let player = Player::from(player_count - 1 - i);
+        game_state.set_player_id(BOT_CLIENT_ID - i as u64, player);
+        bots.insert(player, level);
+        info!("{} is played by the {} AI", player, level);
+    }
+    let mut next_bot_move = Instant::now();
+    let mut next_clock_update = Instant::now();
+
     let mut last_updated = Instant::now();
 
     let mut usernames: HashMap<u64, String> = HashMap::new();
+    let mut replay: Option<ReplayWriter<BufWriter<File>>> = None;
 
     loop {
         let now = Instant::now();
@@ -48,25 +156,42 @@ fn main() {
                 ServerEvent::ClientConnected { client_id } => {
                     info!("Player {} connected", client_id);
 
-                    game_state.set_player_id(
-                        client_id.raw(),
-                        match server.connected_clients() {
-                            1 => store::Player::Red,
-                            2 => store::Player::Blue,
-                            _ => unreachable!(),
-                        },
-                    );
+                    let seat = game_state
+                        .map_config
+                        .players()
+                        .find(|p| !game_state.id_to_player.values().any(|seated| seated == p));
+                    let Some(seat) = seat else {
+                        warn!("Match is full, disconnecting {}", client_id);
+                        server.disconnect(client_id);
+                        continue;
+                    };
+                    game_state.set_player_id(client_id.raw(), seat);
 
                     println!("Player {} is {:?}", client_id, game_state.id_to_player.get(&client_id.raw()));
 
-                    if server.connected_clients() == 2 {
+                    if game_state.id_to_player.len() == game_state.map_config.player_count() {
                         server.broadcast_message(DefaultChannel::ReliableOrdered, bincode::serialize(&StartGame).unwrap());
-                        server.broadcast_message(DefaultChannel::ReliableOrdered, bincode::serialize(&ClientEvent::Init(Box::new(game_state.grid.clone()))).unwrap());
+                        send_events(&mut server, &game_state, &[], &game_state.sync_events());
+
+                        replay = match File::create(&options.replay) {
+                            Ok(file) => ReplayWriter::new(BufWriter::new(file), &game_state)
+                                .map_err(|err| warn!("Not recording a replay: {}", err))
+                                .ok(),
+                            Err(err) => {
+                                warn!("Not recording a replay: {}", err);
+                                None
+                            }
+                        };
                     }
                 }
                 ServerEvent::ClientDisconnected { client_id, reason } => {
                     println!("Player {} disconnected: {}", client_id, reason);
-                    
+
+                    // Clients turned away from a full match don't end it
+                    if !game_state.id_to_player.contains_key(&client_id.raw()) {
+                        continue;
+                    }
+
                     // Exit the server on client disconnect for now
                     std::process::exit(0);
                 }
@@ -74,22 +199,79 @@ fn main() {
         }
 
         for client_id in server.clients_id() {
-            while let Some(message) = server.receive_message(client_id, DefaultChannel::ReliableOrdered) {
-                bincode::deserialize::<TileEvent>(&message).map(|event| {
-                    if let TileEvent::GetUsername { ref username } = event {
-                        usernames.insert(client_id.raw(), username.clone());
-                        return Ok(());
+            while let Some(message) =
+                server.receive_message(client_id, DefaultChannel::ReliableOrdered)
+            {
+                let event = match bincode::deserialize::<TileEvent>(&message) {
+                    Ok(event) => event,
+                    Err(err) => {
+                        warn!("Error: {}", err);
+                        continue;
+                    }
+                };
+
+                if let TileEvent::GetUsername { ref username } = event {
+                    usernames.insert(client_id.raw(), username.clone());
+                    continue;
+                }
+
+                if let Err(err) = play_event(
+                    &mut server,
+                    &mut game_state,
+                    &mut replay,
+                    &options.autosave,
+                    event,
+                ) {
+                    warn!("Rejected action from {}: {}", client_id, err);
+                    server.send_message(
+                        client_id,
+                        DefaultChannel::ReliableOrdered,
+                        bincode::serialize(&ClientEvent::ActionRejected(err)).unwrap(),
+                    );
+                }
+            }
+        }
+
+        let seated = game_state.id_to_player.len() == player_count;
+        if let (true, Some(&level)) = (seated, bots.get(&game_state.turn)) {
+            if now >= next_bot_move {
+                if let Some(event) = choose_event(&game_state, game_state.turn, level) {
+                    if let Err(err) = play_event(
+                        &mut server,
+                        &mut game_state,
+                        &mut replay,
+                        &options.autosave,
+                        event,
+                    ) {
+                        warn!("Rejected bot action: {}", err);
                     }
+                }
+                next_bot_move = Instant::now() + BOT_MOVE_DELAY;
+            }
+        }
+
+        // Clocks only run once everyone is seated
+        if seated {
+            if let Some(action) = game_state.tick(duration) {
+                info!("{} ran out of time", game_state.turn);
+                play_action(
+                    &mut server,
+                    &mut game_state,
+                    &mut replay,
+                    &options.autosave,
+                    TileEvent::None,
+                    action,
+                );
+            }
 
-                    game_state.get_action(&event).map(|action| {
-                        game_state.consume(&action).iter().for_each(|change| {
-                            info!("Sending:\n\t{:#?}", change);
-                            server.broadcast_message(DefaultChannel::ReliableOrdered, bincode::serialize(change).unwrap());
-                        });
-                    }).ok_or(Box::new(ErrorKind::Custom("Invalid action".to_string())))
-                }).map_err(|err| {
-                    warn!("Error: {}", err);
-                }).ok();
+            if now >= next_clock_update {
+                if let Some(event) = game_state.clock_event() {
+                    server.broadcast_message(
+                        DefaultChannel::ReliableOrdered,
+                        bincode::serialize(&event).unwrap(),
+                    );
+                }
+                next_clock_update = now + CLOCK_UPDATE_INTERVAL;
             }
         }
 
@@ -97,3 +279,87 @@ fn main() {
         thread::sleep(Duration::from_millis(10));
     }
 }
+
+/// Sends `events` to every seated client, filtered down to what their player
+/// can see. `before` is every player's vision from before the events.
+fn send_events(
+    server: &mut RenetServer,
+    game_state: &GameState,
+    before: &[Option<Vision>],
+    events: &[ClientEvent],
+) {
+    for client_id in server.clients_id() {
+        let Some(&player) = game_state.id_to_player.get(&client_id.raw()) else {
+            continue;
+        };
+
+        let before = before.get(player as usize).and_then(Option::as_ref);
+        for event in game_state.filter_events(player, before, events) {
+            info!("Sending to {}:\n\t{:#?}", player, event);
+            server.send_message(
+                client_id,
+                DefaultChannel::ReliableOrdered,
+                bincode::serialize(&event).unwrap(),
+            );
+        }
+    }
+}
+
+/// Runs an input through the rules and, if accepted, plays it.
+fn play_event(
+    server: &mut RenetServer,
+    game_state: &mut GameState,
+    replay: &mut Option<ReplayWriter<BufWriter<File>>>,
+    autosave: &Path,
+    event: TileEvent,
+) -> Result<(), RuleError> {
+    let action = game_state.get_action(&event)?;
+    play_action(server, game_state, replay, autosave, event, action);
+
+    Ok(())
+}
+
+/// Consumes `action`, broadcasts the result, records it in the replay and
+/// autosaves at the end of a turn. `event` is the input it came from, or
+/// [`TileEvent::None`] for moves the server makes itself.
+fn play_action(
+    server: &mut RenetServer,
+    game_state: &mut GameState,
+    replay: &mut Option<ReplayWriter<BufWriter<File>>>,
+    autosave: &Path,
+    event: TileEvent,
+    action: GameAction,
+) {
+    let player = game_state.turn;
+    let turn_number = game_state.turn_number;
+    let before = game_state.visions();
+    let changes = game_state.consume(&action);
+    send_events(server, game_state, &before, &changes);
+
+    if let Some(replay) = replay.as_mut() {
+        let entry = ReplayEntry {
+            player,
+            turn_number,
+            timestamp: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+            action: event.is_none().then_some(action),
+            event,
+            changes,
+        };
+        if let Err(err) = replay.record(&entry) {
+            warn!("Failed to record replay: {}", err);
+        }
+    }
+
+    if game_state.turn_number != turn_number || game_state.winner().is_some() {
+        if let Err(err) = game_state.save_to_path(autosave) {
+            warn!("Autosave failed: {:#}", err);
+        }
+    }
+
+    if let Some(winner) = game_state.winner() {
+        info!("Game over, {} wins", winner);
+    }
+}
@@ -0,0 +1,159 @@
+use std::{
+    env,
+    path::PathBuf,
+    process,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use log::error;
+use store::{AiLevel, Mode, TimeControl, Topology, DEFAULT_VISION_RANGE, MAX_PLAYERS};
+
+const USAGE: &str = "Usage: server [--players <2-4>] [--map <path>] [--skip-placement]
+              [--generate] [--seed <n>] [--ai <greedy|search>]...
+              [--replay <path>] [--load <save>] [--autosave <save>]
+              [--turn-time <secs> | --bank <secs> [--increment <secs>]]
+              [--fog] [--vision <range>] [--topology <square|hex>]
+              [--rules <path>]
+              [--mode <classic|king-of-the-hill|capture-all-farms|score-race>
+                [--turn-limit <n>]]";
+
+/// Match settings picked on the command line, e.g. `server --players 3`.
+#[derive(Debug, Clone)]
+pub struct ServerOptions {
+    pub players: usize,
+    /// JSON map to play on instead of the default board. Its bases decide the
+    /// player count.
+    pub map: Option<PathBuf>,
+    /// Start straight in the game phase, keeping only the map's terrain.
+    pub skip_placement: bool,
+    /// Seed for generated terrain, see `store::generate_terrain`. Generated
+    /// maps skip terrain placement.
+    pub seed: Option<u64>,
+    /// One bot per entry, seated from the last seat backwards.
+    pub ai: Vec<AiLevel>,
+    /// Where the match is recorded, see `store::Replay`.
+    pub replay: PathBuf,
+    /// Save to resume instead of starting a new match. Its map decides the
+    /// player count.
+    pub load: Option<PathBuf>,
+    /// Rewritten after every turn, `.json` or bincode depending on extension.
+    pub autosave: PathBuf,
+    /// Untimed unless set, also replaces the clock of a loaded save.
+    pub time_control: Option<TimeControl>,
+    /// Vision range when playing with fog of war, see `store::FogOfWar`.
+    pub fog: Option<usize>,
+    /// Board shape for new matches, overriding the map's own.
+    pub topology: Option<Topology>,
+    /// TOML or JSON rule set for new matches instead of the default rules,
+    /// see `store::RuleSet`.
+    pub rules: Option<PathBuf>,
+    /// Variant to play new matches in, see `store::GameMode`.
+    pub mode: Mode,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        Self {
+            players: 2,
+            map: None,
+            skip_placement: false,
+            seed: None,
+            ai: Vec::new(),
+            replay: PathBuf::from(format!("replay-{}.jsonl", started)),
+            load: None,
+            autosave: PathBuf::from("autosave.json"),
+            time_control: None,
+            fog: None,
+            topology: None,
+            rules: None,
+            mode: Mode::default(),
+        }
+    }
+}
+
+impl ServerOptions {
+    /// Parses the process arguments, exiting with a usage message on anything
+    /// unrecognised.
+    pub fn from_args() -> Self {
+        let mut options = Self::default();
+        let mut args = env::args().skip(1);
+        let mut bank_increment = None;
+        let mut turn_limit = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--players" => {
+                    options.players = parse_value(&arg, args.next());
+                    if !(2..=MAX_PLAYERS).contains(&options.players) {
+                        exit_with_usage(&format!("{} must be between 2 and {}", arg, MAX_PLAYERS));
+                    }
+                }
+                "--map" => options.map = Some(parse_value(&arg, args.next())),
+                "--skip-placement" => options.skip_placement = true,
+                "--generate" => {
+                    let seed = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap()
+                        .as_nanos() as u64;
+                    options.seed = options.seed.or(Some(seed));
+                }
+                "--ai" => options.ai.push(parse_value(&arg, args.next())),
+                "--seed" => options.seed = Some(parse_value(&arg, args.next())),
+                "--replay" => options.replay = parse_value(&arg, args.next()),
+                "--load" => options.load = Some(parse_value(&arg, args.next())),
+                "--autosave" => options.autosave = parse_value(&arg, args.next()),
+                "--turn-time" => {
+                    let secs = parse_value(&arg, args.next());
+                    options.time_control = Some(TimeControl::PerTurn(Duration::from_secs(secs)));
+                }
+                "--bank" => {
+                    options.time_control = Some(TimeControl::Bank {
+                        initial: Duration::from_secs(parse_value(&arg, args.next())),
+                        increment: Duration::ZERO,
+                    });
+                }
+                "--increment" => {
+                    bank_increment = Some(Duration::from_secs(parse_value(&arg, args.next())))
+                }
+                "--fog" => options.fog = options.fog.or(Some(DEFAULT_VISION_RANGE)),
+                "--vision" => options.fog = Some(parse_value(&arg, args.next())),
+                "--topology" => options.topology = Some(parse_value(&arg, args.next())),
+                "--rules" => options.rules = Some(parse_value(&arg, args.next())),
+                "--mode" => options.mode = parse_value(&arg, args.next()),
+                "--turn-limit" => turn_limit = Some(parse_value(&arg, args.next())),
+                _ => exit_with_usage(&format!("Unknown argument {}", arg)),
+            }
+        }
+
+        match (&mut options.time_control, bank_increment) {
+            (Some(TimeControl::Bank { increment, .. }), Some(time)) => *increment = time,
+            (_, Some(_)) => exit_with_usage("--increment needs --bank"),
+            _ => (),
+        }
+
+        match (&mut options.mode, turn_limit) {
+            (Mode::ScoreRace { turns }, Some(limit)) => *turns = limit,
+            (_, Some(_)) => exit_with_usage("--turn-limit needs --mode score-race"),
+            _ => (),
+        }
+
+        options
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> T {
+    value
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_else(|| exit_with_usage(&format!("Missing or invalid value for {}", flag)))
+}
+
+fn exit_with_usage(message: &str) -> ! {
+    error!("{}", message);
+    eprintln!("{}", USAGE);
+    process::exit(1)
+}
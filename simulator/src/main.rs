@@ -0,0 +1,148 @@
+use std::{fs::File, io::BufWriter, process, thread};
+
+use log::{error, info};
+use options::{exit_with_usage, SimulatorOptions};
+use policy::{action_kind, Policy, ACTION_KINDS};
+use rand::{rngs::StdRng, SeedableRng};
+use report::{GameResult, Report};
+use store::{
+    generate_terrain, GameState, MapConfig, Player, RuleSet, DEFAULT_MAP_HEIGHT, DEFAULT_MAP_WIDTH,
+};
+
+mod options;
+mod policy;
+mod report;
+
+/// Plays batches of games between policies without a window or a network,
+/// straight on `store::GameState`, and reports how each side fared.
+fn main() {
+    env_logger::init();
+    let options = SimulatorOptions::from_args();
+
+    let mut map_config = match &options.map {
+        Some(path) => MapConfig::load_from_path(path).unwrap_or_else(|err| {
+            error!("Failed to load {}: {:#}", path.display(), err);
+            process::exit(1)
+        }),
+        None => MapConfig::with_players(DEFAULT_MAP_WIDTH, DEFAULT_MAP_HEIGHT, options.players),
+    };
+    if let Some(budget) = options.mountain_budget {
+        map_config.mountain_budget = Some(budget);
+    }
+    if let Some(budget) = options.water_budget {
+        map_config.water_budget = Some(budget);
+    }
+
+    let rules = match &options.rules {
+        Some(path) => RuleSet::load_from_path(path).unwrap_or_else(|err| {
+            error!("Failed to load {}: {:#}", path.display(), err);
+            process::exit(1)
+        }),
+        None => RuleSet::default(),
+    };
+
+    let player_count = map_config.player_count();
+    if options.policies.len() > player_count {
+        exit_with_usage(&format!("This map only has {} seats", player_count));
+    }
+    let mut policies = options.policies.clone();
+    policies.resize(player_count, Policy::Greedy);
+
+    info!(
+        "Playing {} games of {}",
+        options.games,
+        policies
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join(" vs ")
+    );
+
+    let workers = thread::available_parallelism().map_or(1, |n| n.get());
+    let mut results = thread::scope(|scope| {
+        let handles = (0..workers)
+            .map(|worker| {
+                let (options, map_config, rules, policies) =
+                    (&options, &map_config, &rules, &policies);
+                scope.spawn(move || {
+                    (worker..options.games)
+                        .step_by(workers)
+                        .map(|game| (game, play(options, map_config, rules, policies, game)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    results.sort_by_key(|&(game, _)| game);
+    let results = results
+        .into_iter()
+        .map(|(_, result)| result)
+        .collect::<Vec<_>>();
+
+    let report = Report::new(&policies, &results);
+    let written = match &options.output {
+        Some(path) => {
+            File::create(path).and_then(|file| report.write(BufWriter::new(file), options.format))
+        }
+        None => report.write(std::io::stdout().lock(), options.format),
+    };
+    if let Err(err) = written {
+        error!("Failed to write the report: {}", err);
+        process::exit(1);
+    }
+}
+
+/// Plays game number `game` to the end or the turn limit. The game number
+/// seeds both the terrain and the random policies, so a batch always gives
+/// the same results.
+fn play(
+    options: &SimulatorOptions,
+    map_config: &MapConfig,
+    rules: &RuleSet,
+    policies: &[Policy],
+    game: usize,
+) -> GameResult {
+    let map_config = match options.seed {
+        Some(seed) => generate_terrain(map_config, seed.wrapping_add(game as u64)),
+        None => map_config.clone(),
+    };
+    let mut state = GameState::with_rules(map_config, rules.clone());
+    if !options.placement {
+        state.skip_terrain_placement();
+    }
+
+    // Side `i` sits in seat `i`, moved along by one each game when rotating
+    let offset = if options.rotate {
+        game % policies.len()
+    } else {
+        0
+    };
+    let side_of = |player: Player| (player as usize + policies.len() - offset) % policies.len();
+    for player in state.map_config.players().collect::<Vec<_>>() {
+        state.set_player_id(player as u64 + 1, player);
+    }
+
+    let mut rng = StdRng::seed_from_u64(options.seed.unwrap_or(0) ^ game as u64);
+    let mut actions = vec![[0; ACTION_KINDS.len()]; policies.len()];
+    while state.winner().is_none() && state.turn_number < options.max_turns {
+        let side = side_of(state.turn);
+        let Some(action) = policies[side].choose(&state, state.turn, &mut rng) else {
+            break;
+        };
+
+        let kind = action_kind(&state, &action);
+        actions[side][ACTION_KINDS.iter().position(|&k| k == kind).unwrap()] += 1;
+        state.consume(&action);
+    }
+
+    GameResult {
+        winner: state.winner().map(side_of),
+        turns: state.turn_number,
+        actions,
+    }
+}
@@ -0,0 +1,113 @@
+use std::{env, path::PathBuf, process};
+
+use log::error;
+use store::MAX_PLAYERS;
+
+use crate::{policy::Policy, report::Format};
+
+const USAGE: &str = "Usage: simulator [--games <n>] [--players <2-4>] [--map <path>]
+                 [--policy <random|greedy|search|farmer|upgrader|builder|attacker>]...
+                 [--rotate] [--seed <n>] [--placement] [--max-turns <n>]
+                 [--mountain-budget <n>] [--water-budget <n>] [--rules <path>]
+                 [--format <csv|json>] [--output <path>]";
+
+/// Batch settings picked on the command line, e.g.
+/// `simulator --games 1000 --policy farmer --policy upgrader --rotate`.
+#[derive(Debug, Clone)]
+pub struct SimulatorOptions {
+    pub games: usize,
+    pub players: usize,
+    /// JSON map to play on instead of the default board. Its bases decide the
+    /// player count.
+    pub map: Option<PathBuf>,
+    /// One policy per side, in seat order. Missing sides play greedy.
+    pub policies: Vec<Policy>,
+    /// Shift every side one seat along each game, so no policy profits from
+    /// always moving first.
+    pub rotate: bool,
+    /// Game `i` generates its terrain with `seed + i`, see
+    /// `store::generate_terrain`. Without a seed the map is played as is.
+    pub seed: Option<u64>,
+    /// Play terrain placement instead of starting straight in the game phase.
+    pub placement: bool,
+    /// Turns after which a game is called a draw. Evenly matched bots can
+    /// trade the same tiles forever.
+    pub max_turns: usize,
+    pub mountain_budget: Option<usize>,
+    pub water_budget: Option<usize>,
+    /// TOML or JSON rule set to play by instead of the default rules, see
+    /// `store::RuleSet`.
+    pub rules: Option<PathBuf>,
+    pub format: Format,
+    /// Where the report is written, stdout if unset.
+    pub output: Option<PathBuf>,
+}
+
+impl Default for SimulatorOptions {
+    fn default() -> Self {
+        Self {
+            games: 100,
+            players: 2,
+            map: None,
+            policies: Vec::new(),
+            rotate: false,
+            seed: None,
+            placement: false,
+            max_turns: 500,
+            mountain_budget: None,
+            water_budget: None,
+            rules: None,
+            format: Format::Csv,
+            output: None,
+        }
+    }
+}
+
+impl SimulatorOptions {
+    /// Parses the process arguments, exiting with a usage message on anything
+    /// unrecognised.
+    pub fn from_args() -> Self {
+        let mut options = Self::default();
+        let mut args = env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--games" => options.games = parse_value(&arg, args.next()),
+                "--players" => {
+                    options.players = parse_value(&arg, args.next());
+                    if !(2..=MAX_PLAYERS).contains(&options.players) {
+                        exit_with_usage(&format!("{} must be between 2 and {}", arg, MAX_PLAYERS));
+                    }
+                }
+                "--map" => options.map = Some(parse_value(&arg, args.next())),
+                "--policy" => options.policies.push(parse_value(&arg, args.next())),
+                "--rotate" => options.rotate = true,
+                "--seed" => options.seed = Some(parse_value(&arg, args.next())),
+                "--placement" => options.placement = true,
+                "--max-turns" => options.max_turns = parse_value(&arg, args.next()),
+                "--mountain-budget" => {
+                    options.mountain_budget = Some(parse_value(&arg, args.next()))
+                }
+                "--water-budget" => options.water_budget = Some(parse_value(&arg, args.next())),
+                "--rules" => options.rules = Some(parse_value(&arg, args.next())),
+                "--format" => options.format = parse_value(&arg, args.next()),
+                "--output" => options.output = Some(parse_value(&arg, args.next())),
+                _ => exit_with_usage(&format!("Unknown argument {}", arg)),
+            }
+        }
+
+        options
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> T {
+    value
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_else(|| exit_with_usage(&format!("Missing or invalid value for {}", flag)))
+}
+
+pub fn exit_with_usage(message: &str) -> ! {
+    error!("{}", message);
+    eprintln!("{}", USAGE);
+    process::exit(1)
+}
@@ -0,0 +1,154 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+use store::{choose_event, AiLevel, GameAction, GamePhase, GameState, Player};
+use strum::{Display, EnumString};
+
+/// Who plays a side. The scripted policies each lean on one part of the
+/// rules so their win rates say how strong that part is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, Serialize, Deserialize)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum Policy {
+    /// Any legal move.
+    Random,
+    Greedy,
+    Search,
+    /// Splits its moves between building and upgrading farms and attacking.
+    Farmer,
+    /// Splits its moves between upgrading tiles and attacking.
+    Upgrader,
+    /// Splits its moves between building and upgrading towers and attacking.
+    Builder,
+    /// Always attacks, spending each action point on the biggest attack the
+    /// stockpile pays for.
+    Attacker,
+}
+
+/// How often a scripted policy takes its favourite action over an attack.
+const FAVOURITE_CHANCE: f64 = 0.5;
+
+impl Policy {
+    /// The action `player` takes next, or `None` if they can't move.
+    ///
+    /// Every policy leaves terrain placement to the greedy AI, so they are
+    /// compared on how they play the game itself. The scripted policies never
+    /// select, they attack from whichever tile is adjacent to the target.
+    pub fn choose(self, state: &GameState, player: Player, rng: &mut StdRng) -> Option<GameAction> {
+        let level = match self {
+            Policy::Search => AiLevel::Search,
+            _ if state.game_phase != GamePhase::Game => AiLevel::Greedy,
+            Policy::Greedy => AiLevel::Greedy,
+            scripted => {
+                let actions = state
+                    .legal_actions(player)
+                    .into_iter()
+                    .filter(|action| {
+                        !matches!(action, GameAction::Select(_) | GameAction::Deselect)
+                    })
+                    .collect::<Vec<_>>();
+                return scripted.pick(state, &actions, rng).cloned();
+            }
+        };
+
+        let event = choose_event(state, player, level)?;
+        state.get_action(&event).ok()
+    }
+
+    /// Takes a favourite action half of the time and attacks otherwise, so
+    /// the scripted policies still push towards a win.
+    fn pick<'a>(
+        self,
+        state: &GameState,
+        actions: &'a [GameAction],
+        rng: &mut StdRng,
+    ) -> Option<&'a GameAction> {
+        let is_farm = |position| state.grid.get_tile(position).is_farm();
+        let is_tower = |position| state.grid.get_tile(position).is_tower();
+        let favourites = actions
+            .iter()
+            .filter(|action| match (self, action) {
+                (Policy::Farmer, GameAction::MakeFarm(_)) => true,
+                (Policy::Farmer, GameAction::Upgrade(p)) => is_farm(*p),
+                (Policy::Upgrader, GameAction::Upgrade(p)) => !is_farm(*p) && !is_tower(*p),
+                (Policy::Builder, GameAction::MakeTower(_)) => true,
+                (Policy::Builder, GameAction::Upgrade(p)) => is_tower(*p),
+                (Policy::Attacker, GameAction::Attack(_)) => true,
+                (Policy::Random, _) => true,
+                _ => false,
+            })
+            .collect::<Vec<_>>();
+        let attacks = actions
+            .iter()
+            .filter(|action| matches!(action, GameAction::Attack(_)))
+            .collect::<Vec<_>>();
+
+        let pool = if self == Policy::Attacker {
+            // The most tiles one action point hits that the stockpile covers
+            let most = attacks.iter().map(|a| attack_size(a)).max().unwrap_or(0);
+            attacks
+                .into_iter()
+                .filter(|a| attack_size(a) == most)
+                .collect()
+        } else if !favourites.is_empty()
+            && (self == Policy::Random || rng.gen_bool(FAVOURITE_CHANCE))
+        {
+            favourites
+        } else {
+            attacks
+        };
+
+        match pool.choose(rng) {
+            Some(action) => Some(action),
+            None => actions.choose(rng),
+        }
+    }
+}
+
+fn attack_size(action: &GameAction) -> usize {
+    match action {
+        GameAction::Attack(targets) => targets.len(),
+        _ => 0,
+    }
+}
+
+/// Name of the kind of `action`, used as a column in the report. Upgrades
+/// are split by what gets upgraded, that's what the balance argument is
+/// about.
+pub fn action_kind(state: &GameState, action: &GameAction) -> &'static str {
+    match action {
+        GameAction::Attack(_) => "attack",
+        GameAction::MakeFarm(_) => "make_farm",
+        GameAction::MakeTower(_) => "make_tower",
+        GameAction::Upgrade(position) if state.grid.get_tile(*position).is_farm() => "upgrade_farm",
+        GameAction::Upgrade(position) if state.grid.get_tile(*position).is_tower() => {
+            "upgrade_tower"
+        }
+        GameAction::Upgrade(_) => "upgrade_tile",
+        GameAction::Repair(_) => "repair",
+        GameAction::Select(_) => "select",
+        GameAction::Deselect => "deselect",
+        GameAction::MakeTerrain(..) => "make_terrain",
+        GameAction::SetTerrainMode(_) => "set_terrain_mode",
+        GameAction::EndTerrainPlacement => "end_terrain_placement",
+        GameAction::EndTurn => "end_turn",
+        GameAction::Timeout => "timeout",
+    }
+}
+
+/// Every value [`action_kind`] returns, in report order.
+pub const ACTION_KINDS: [&str; 14] = [
+    "attack",
+    "make_farm",
+    "make_tower",
+    "upgrade_farm",
+    "upgrade_tower",
+    "upgrade_tile",
+    "repair",
+    "select",
+    "deselect",
+    "make_terrain",
+    "set_terrain_mode",
+    "end_terrain_placement",
+    "end_turn",
+    "timeout",
+];
@@ -0,0 +1,125 @@
+use std::{collections::BTreeMap, io::Write};
+
+use serde::Serialize;
+use strum::{Display, EnumString};
+
+use crate::policy::{Policy, ACTION_KINDS};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Format {
+    Csv,
+    Json,
+}
+
+/// How a single game went, sides indexed like the `--policy` arguments.
+#[derive(Debug, Clone)]
+pub struct GameResult {
+    /// `None` if the game hit the turn limit.
+    pub winner: Option<usize>,
+    pub turns: usize,
+    /// Actions taken by each side, indexed like [`ACTION_KINDS`].
+    pub actions: Vec<[usize; ACTION_KINDS.len()]>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub games: usize,
+    pub draws: usize,
+    pub average_turns: f64,
+    pub sides: Vec<SideReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SideReport {
+    pub side: usize,
+    pub policy: Policy,
+    pub wins: usize,
+    pub win_rate: f64,
+    /// Total actions of each kind over every game.
+    pub actions: BTreeMap<&'static str, usize>,
+}
+
+impl Report {
+    pub fn new(policies: &[Policy], results: &[GameResult]) -> Self {
+        let games = results.len();
+        let rate = |count: usize| {
+            if games == 0 {
+                0.0
+            } else {
+                count as f64 / games as f64
+            }
+        };
+
+        let sides = policies
+            .iter()
+            .enumerate()
+            .map(|(side, &policy)| {
+                let wins = results.iter().filter(|r| r.winner == Some(side)).count();
+                let actions = ACTION_KINDS
+                    .iter()
+                    .enumerate()
+                    .map(|(kind, &name)| {
+                        (name, results.iter().map(|r| r.actions[side][kind]).sum())
+                    })
+                    .collect();
+
+                SideReport {
+                    side,
+                    policy,
+                    wins,
+                    win_rate: rate(wins),
+                    actions,
+                }
+            })
+            .collect();
+
+        let draws = results.iter().filter(|r| r.winner.is_none()).count();
+        Self {
+            games,
+            draws,
+            average_turns: rate(results.iter().map(|r| r.turns).sum()),
+            sides,
+        }
+    }
+
+    pub fn write(&self, mut writer: impl Write, format: Format) -> std::io::Result<()> {
+        match format {
+            Format::Json => serde_json::to_writer_pretty(&mut writer, self)?,
+            Format::Csv => self.write_csv(&mut writer)?,
+        }
+        writeln!(writer)?;
+        writer.flush()
+    }
+
+    /// One row per side, game totals repeated on every row so each can be
+    /// read on its own.
+    fn write_csv(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        write!(
+            writer,
+            "side,policy,games,wins,win_rate,draws,average_turns"
+        )?;
+        for kind in ACTION_KINDS {
+            write!(writer, ",{}", kind)?;
+        }
+
+        for side in &self.sides {
+            write!(
+                writer,
+                "\n{},{},{},{},{:.4},{},{:.2}",
+                side.side,
+                side.policy,
+                self.games,
+                side.wins,
+                side.win_rate,
+                self.draws,
+                self.average_turns
+            )?;
+            for kind in ACTION_KINDS {
+                write!(writer, ",{}", side.actions[kind])?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use store::*;
+
+/// A square board split down the middle, red's tiles filling the left half
+/// and blue's the right. Every eighth column is a wall with a single gap,
+/// alternating top and bottom, so the paths back to each base wind through
+/// the whole half.
+fn board(size: usize) -> TileGrid {
+    let config = MapConfig::new(size, size);
+    let mut grid = TileGrid::new(&config, &RuleSet::default());
+    let GridPos(min_x, min_y) = grid.origin();
+
+    for i in 0..size * size {
+        let position @ GridPos(x, y) = grid.get_pos_from_index(i);
+        let (column, row) = (x - min_x, y - min_y);
+        let gap = if column % 16 == 7 { 0 } else { size as i32 - 1 };
+        if grid.get_tile(position) != TileType::EMPTY || x == 0 || (column % 8 == 7 && row != gap) {
+            continue;
+        }
+
+        let owner = if x < 0 { Player::Red } else { Player::Blue };
+        grid.set_tile(
+            position,
+            TileType::EMPTY.with_owner(PlayerTile::Tile, owner, 1, 1),
+        );
+    }
+
+    grid
+}
+
+/// How [`TileGrid::update`] used to find disconnected tiles, searching for
+/// the base from every tile.
+fn update_by_search(grid: &mut TileGrid) -> Vec<TileChange> {
+    let mut to_remove = Vec::new();
+
+    for (i, tile) in grid.get_tiles().enumerate() {
+        if let TileType::Occupied {
+            player_tile: PlayerTile::Farm | PlayerTile::Tile | PlayerTile::Tower,
+            owner,
+            ..
+        } = *tile
+        {
+            if !grid.is_connected_to_base(grid.get_pos_from_index(i), owner) {
+                to_remove.push(i);
+            }
+        }
+    }
+
+    to_remove
+        .into_iter()
+        .map(|i| {
+            grid.grid[i] = TileType::EMPTY;
+            TileChange {
+                position: grid.get_pos_from_index(i),
+                tile: TileType::EMPTY,
+            }
+        })
+        .collect()
+}
+
+fn sweep(c: &mut Criterion) {
+    let mut group = c.benchmark_group("disconnection sweep");
+    group.sample_size(10);
+
+    for size in [16, 32, 64] {
+        let grid = board(size);
+        group.bench_with_input(BenchmarkId::new("search", size), &grid, |b, grid| {
+            b.iter_batched(
+                || grid.clone(),
+                |mut grid| update_by_search(&mut grid),
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("flood fill", size), &grid, |b, grid| {
+            b.iter_batched(
+                || grid.clone(),
+                |mut grid| grid.update(),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, sweep);
+criterion_main!(benches);
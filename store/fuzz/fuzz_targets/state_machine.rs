@@ -0,0 +1,60 @@
+#![no_main]
+
+use bincode::Options;
+use libfuzzer_sys::fuzz_target;
+use serde::Deserialize;
+use store::*;
+
+// The first bytes pick the board, the rest is a stream of bincode encoded
+// client messages played against it like the server would.
+fuzz_target!(|data: &[u8]| {
+    let [width, height, players, flags, events @ ..] = data else {
+        return;
+    };
+
+    let width = 2 + *width as usize % 16;
+    let height = 2 + *height as usize % 10;
+    let players = 2 + *players as usize % (MAX_PLAYERS - 1);
+    let map_config = MapConfig::with_players(width, height, players);
+    let map_config = if flags & 1 != 0 {
+        generate_terrain(&map_config, *flags as u64)
+    } else {
+        map_config
+    };
+
+    let mut state = GameState::new(map_config);
+    for player in state.map_config.players().collect::<Vec<_>>() {
+        state.set_player_id(player as u64, player);
+    }
+    if flags & 2 != 0 {
+        state.skip_terrain_placement();
+    }
+
+    // Same encoding as `bincode::deserialize`, read from a slice so a bogus
+    // length can't make us allocate more than the input
+    let options = bincode::options()
+        .with_fixint_encoding()
+        .allow_trailing_bytes();
+    let mut deserializer = bincode::Deserializer::from_slice(events, options);
+
+    while let Ok(event) = TileEvent::deserialize(&mut deserializer) {
+        let Ok(action) = state.get_action(&event) else {
+            continue;
+        };
+        state.consume(&action);
+
+        for (i, tile) in state.grid.get_tiles().enumerate() {
+            if let TileType::Occupied {
+                player_tile: PlayerTile::Farm | PlayerTile::Tile | PlayerTile::Tower,
+                owner,
+                ..
+            } = *tile
+            {
+                assert!(state
+                    .grid
+                    .is_connected_to_base(state.grid.get_pos_from_index(i), owner));
+            }
+        }
+        assert_eq!(state.economy.balance.len(), state.map_config.player_count());
+    }
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use store::TileEvent;
+
+// Exactly what the server does with every message a client sends
+fuzz_target!(|data: &[u8]| {
+    if let Ok(event) = bincode::deserialize::<TileEvent>(data) {
+        let bytes = bincode::serialize(&event).unwrap();
+        assert_eq!(bincode::deserialize::<TileEvent>(&bytes).unwrap(), event);
+    }
+});
@@ -0,0 +1,293 @@
+use strum::{Display, EnumIter, EnumString};
+
+use crate::*;
+
+/// How hard a computer controlled seat tries.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, EnumIter, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "lowercase")]
+pub enum AiLevel {
+    /// Plays whatever looks best right after its own move.
+    Greedy,
+    /// Looks [`SEARCH_DEPTH`] turns ahead, see [`search`].
+    Search,
+}
+
+/// How many of the greedy picks the search level looks into, on every turn
+/// it looks at.
+const SEARCH_WIDTH: usize = 12;
+/// Turns the search level looks at: its own and the next player's reply.
+pub const SEARCH_DEPTH: usize = 2;
+
+const WIN_SCORE: i64 = 1_000_000;
+/// Score per step between our closest tile and an enemy base.
+const ATTACK_WEIGHT: i64 = 20;
+/// Score per step between the closest enemy tile and our base.
+const THREAT_WEIGHT: i64 = 10;
+/// Score lost for each tile that falls to a single enemy attack.
+const EXPOSED_PENALTY: i64 = 15;
+/// Score per resource in the stockpile, low enough that spending pays off.
+const STOCKPILE_WEIGHT: i64 = 2;
+/// Score per resource collected each turn.
+const INCOME_WEIGHT: i64 = 10;
+
+/// The input `player` would send next, or `None` if it isn't their turn or
+/// they have no seat. Everything returned is accepted by
+/// [`GameState::get_action`].
+pub fn choose_event(state: &GameState, player: Player, level: AiLevel) -> Option<TileEvent> {
+    if state.turn != player || state.winner().is_some() {
+        return None;
+    }
+    match state.game_phase {
+        GamePhase::TerrainPlacement => place_terrain(state, player),
+        GamePhase::Game => {
+            let moves = candidate_events(state, player)
+                .into_iter()
+                .filter_map(|event| after(state, &event).map(|next| (event, next)));
+
+            let scored = match level {
+                AiLevel::Greedy => moves
+                    .map(|(event, next)| (evaluate(&next, player), event))
+                    .collect(),
+                AiLevel::Search => best_moves(moves, player)
+                    .map(|(event, next)| {
+                        let next = finish_turn(&next, player);
+                        (search(&next, player, SEARCH_DEPTH - 1), event)
+                    })
+                    .collect(),
+            };
+            pick_best(scored, state.turn_number)
+        }
+        GamePhase::Finished { .. } => None,
+    }
+}
+
+/// The highest scoring event. Ties are broken by turn number rather than
+/// board order, otherwise two bots happily trade the same tile forever.
+fn pick_best(scored: Vec<(i64, TileEvent)>, turn_number: usize) -> Option<TileEvent> {
+    let best = scored.iter().map(|&(score, _)| score).max()?;
+    let mut ties = scored
+        .into_iter()
+        .filter(|&(score, _)| score == best)
+        .map(|(_, event)| event)
+        .collect::<Vec<_>>();
+
+    let pick = turn_number % ties.len();
+    Some(ties.swap_remove(pick))
+}
+
+/// Scores `state` from `player`'s point of view, higher is better.
+pub fn evaluate(state: &GameState, player: Player) -> i64 {
+    match state.game_phase {
+        GamePhase::Finished { winner, .. } if winner == player => return WIN_SCORE,
+        GamePhase::Finished { .. } => return -WIN_SCORE,
+        _ if !state.players.contains(&player) => return -WIN_SCORE,
+        _ => (),
+    }
+
+    let opponents = state
+        .players
+        .iter()
+        .copied()
+        .filter(|&p| p != player)
+        .collect::<Vec<_>>();
+    let opponent_strength = opponents.iter().map(|&p| strength(state, p)).sum::<i64>();
+
+    // Closing in on a base is worth something long before it pays off
+    let attack = opponents
+        .iter()
+        .map(|&p| closest_to_base(state, &[player], p))
+        .min()
+        .unwrap_or(0);
+    let threat = closest_to_base(state, &opponents, player);
+
+    strength(state, player) * opponents.len().max(1) as i64
+        - opponent_strength
+        - ATTACK_WEIGHT * attack
+        + THREAT_WEIGHT * threat
+}
+
+/// Fewest steps from any tile owned by `owners` to `target`'s base.
+fn closest_to_base(state: &GameState, owners: &[Player], target: Player) -> i64 {
+    let Some(base) = state.map_config.base(target) else {
+        return 0;
+    };
+
+    state
+        .grid
+        .get_tiles()
+        .enumerate()
+        .filter(|(_, tile)| tile.owner().is_some_and(|p| owners.contains(&p)))
+        .map(|(i, _)| distance(state, state.grid.get_pos_from_index(i), base))
+        .min()
+        .unwrap_or(0)
+}
+
+fn strength(state: &GameState, player: Player) -> i64 {
+    let board = state
+        .grid
+        .get_tiles()
+        .enumerate()
+        .filter(|(_, tile)| tile.owner() == Some(player))
+        .map(|(i, tile)| match *tile {
+            TileType::Occupied {
+                player_tile: PlayerTile::Base,
+                hp,
+                ..
+            } => 30 * hp as i64,
+            TileType::Occupied {
+                player_tile: PlayerTile::Farm,
+                level,
+                ..
+            } => 15 * level as i64,
+            TileType::Occupied { level, hp, .. } => {
+                // A tile the enemy takes with one hit is barely ours
+                let position = state.grid.get_pos_from_index(i);
+                let exposed = hp <= 1
+                    && state.grid.topology.neighbours(position).any(|next| {
+                        state
+                            .grid
+                            .get_tile(next)
+                            .owner()
+                            .is_some_and(|p| p != player)
+                    });
+
+                15 * level as i64 + 5 * hp as i64 - if exposed { EXPOSED_PENALTY } else { 0 }
+            }
+            _ => 0,
+        })
+        .sum::<i64>();
+
+    let stockpile = state.economy.balance(player) as i64;
+    let income = Economy::income(&state.rules, &state.grid, player) as i64;
+
+    board + STOCKPILE_WEIGHT * stockpile + INCOME_WEIGHT * income
+}
+
+/// The score `player` can count on `depth` turns after `state`. Whoever is
+/// on turn tries each of their [`SEARCH_WIDTH`] greedy favourites, plays the
+/// rest of their turn greedily and takes what is best for them, everyone
+/// else playing against `player`.
+pub fn search(state: &GameState, player: Player, depth: usize) -> i64 {
+    let mover = state.turn;
+    if depth == 0 || state.winner().is_some() || !state.players.contains(&player) {
+        return evaluate(state, player);
+    }
+
+    let moves = candidate_events(state, mover)
+        .into_iter()
+        .filter_map(|event| after(state, &event).map(|next| (event, next)));
+    let scores = best_moves(moves, mover).map(|(_, next)| {
+        let next = finish_turn(&next, mover);
+        search(&next, player, depth - 1)
+    });
+
+    let score = if mover == player {
+        scores.max()
+    } else {
+        scores.min()
+    };
+    score.unwrap_or_else(|| evaluate(state, player))
+}
+
+/// The [`SEARCH_WIDTH`] of `moves` that look best to `player` right after
+/// them.
+fn best_moves(
+    moves: impl Iterator<Item = (TileEvent, GameState)>,
+    player: Player,
+) -> impl Iterator<Item = (TileEvent, GameState)> {
+    let mut moves = moves
+        .map(|(event, next)| (evaluate(&next, player), event, next))
+        .collect::<Vec<_>>();
+    moves.sort_by_key(|&(score, ..)| -score);
+    moves
+        .into_iter()
+        .take(SEARCH_WIDTH)
+        .map(|(_, event, next)| (event, next))
+}
+
+/// `state` once `player` has spent the rest of their turn on whatever looks
+/// best right after each move.
+fn finish_turn(state: &GameState, player: Player) -> GameState {
+    let mut state = state.clone();
+
+    while state.turn == player && state.winner().is_none() {
+        // Only moves that spend action points or end the turn, so this ends
+        let next = candidate_events(&state, player)
+            .iter()
+            .filter_map(|event| after(&state, event))
+            .filter(|next| next.turn != player || next.action_points < state.action_points)
+            .max_by_key(|next| evaluate(next, player));
+        match next {
+            Some(next) => state = next,
+            None => break,
+        }
+    }
+
+    state
+}
+
+/// Every click on the board that the rules accept right now, and ending the
+/// turn early. Selection isn't needed, attacks are launched from any adjacent
+/// tile.
+fn candidate_events(state: &GameState, player: Player) -> Vec<TileEvent> {
+    state
+        .legal_events(player)
+        .into_iter()
+        .filter(|event| {
+            matches!(
+                event,
+                TileEvent::TileAction { .. } | TileEvent::EndTurn { .. }
+            )
+        })
+        .collect()
+}
+
+/// Spends the mountain budget as close to home as possible, then ends the
+/// placement turn. Captured mountains make sturdy tiles, so they are worth
+/// more near our own base than anywhere else.
+fn place_terrain(state: &GameState, player: Player) -> Option<TileEvent> {
+    let events = state
+        .legal_events(player)
+        .into_iter()
+        .filter_map(|event| Some((state.get_action(&event).ok()?, event)))
+        .collect::<Vec<_>>();
+    let find = |wanted: GameAction| {
+        events
+            .iter()
+            .find(|(action, _)| *action == wanted)
+            .map(|(_, event)| event.clone())
+    };
+
+    let terrain = &state.terrain_controller;
+    let mountains_left = terrain.mountain_count[player as usize] < state.rules.mountain_budget;
+    if mountains_left && terrain.placement_mode != Terrain::Mountain {
+        return find(GameAction::SetTerrainMode(Terrain::Mountain));
+    }
+
+    let base = state.map_config.base(player)?;
+    let mountain = events
+        .iter()
+        .filter_map(|(action, event)| match action {
+            GameAction::MakeTerrain(position, Terrain::Mountain) => Some((*position, event)),
+            _ => None,
+        })
+        .min_by_key(|&(position, _)| distance(state, position, base));
+
+    match mountain {
+        Some((_, event)) if mountains_left => Some(event.clone()),
+        _ => find(GameAction::EndTerrainPlacement),
+    }
+}
+
+fn after(state: &GameState, event: &TileEvent) -> Option<GameState> {
+    let action = state.get_action(event).ok()?;
+    let mut next = state.clone();
+    next.consume(&action);
+    Some(next)
+}
+
+fn distance(state: &GameState, a: GridPos, b: GridPos) -> i64 {
+    state.grid.topology.distance(a, b) as i64
+}
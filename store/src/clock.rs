@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use crate::*;
+
+/// How much thinking time players get, picked by the server per match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TimeControl {
+    /// Every turn starts with the same time, running out skips the turn.
+    PerTurn(Duration),
+    /// One bank for the whole match, topped up by `increment` after each of
+    /// the player's turns. Running out loses the match.
+    Bank {
+        initial: Duration,
+        increment: Duration,
+    },
+}
+
+/// Time left for every seat. Only ticks for the player on turn, the server
+/// decides how much time has passed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Clock {
+    pub control: Option<TimeControl>,
+    /// Indexed by `Player as usize`.
+    pub remaining: Vec<Duration>,
+}
+
+impl Clock {
+    pub fn new(control: Option<TimeControl>, players: usize) -> Self {
+        let start = match control {
+            Some(TimeControl::PerTurn(time)) => time,
+            Some(TimeControl::Bank { initial, .. }) => initial,
+            None => Duration::ZERO,
+        };
+
+        Self {
+            control,
+            remaining: vec![start; players],
+        }
+    }
+
+    pub fn remaining(&self, player: Player) -> Option<Duration> {
+        self.control?;
+        self.remaining.get(player as usize).copied()
+    }
+
+    /// Takes `elapsed` off `player`'s time, returning whether they ran out.
+    pub fn tick(&mut self, player: Player, elapsed: Duration) -> bool {
+        if self.control.is_none() {
+            return false;
+        }
+
+        let Some(remaining) = self.remaining.get_mut(player as usize) else {
+            return false;
+        };
+        *remaining = remaining.saturating_sub(elapsed);
+        remaining.is_zero()
+    }
+
+    /// Hands the clock from `previous` to `next`.
+    pub fn switch(&mut self, previous: Player, next: Player) {
+        match self.control {
+            Some(TimeControl::PerTurn(time)) => {
+                if let Some(remaining) = self.remaining.get_mut(next as usize) {
+                    *remaining = time;
+                }
+            }
+            Some(TimeControl::Bank { increment, .. }) => {
+                if let Some(remaining) = self.remaining.get_mut(previous as usize) {
+                    *remaining += increment;
+                }
+            }
+            None => (),
+        }
+    }
+}
+
+impl GameState {
+    /// Replaces the clock, giving every seat a fresh allowance.
+    pub fn set_time_control(&mut self, control: TimeControl) {
+        self.clock = Clock::new(Some(control), self.map_config.player_count());
+    }
+
+    /// Runs the clock of the player on turn. Once their time is up, returns
+    /// the [`GameAction::Timeout`] the server should play for them.
+    pub fn tick(&mut self, elapsed: Duration) -> Option<GameAction> {
+        if self.winner().is_some() {
+            return None;
+        }
+
+        self.clock
+            .tick(self.turn, elapsed)
+            .then_some(GameAction::Timeout)
+    }
+
+    /// The countdown of the player on turn, if the match is timed.
+    pub fn clock_event(&self) -> Option<ClientEvent> {
+        if self.winner().is_some() {
+            return None;
+        }
+
+        Some(ClientEvent::Clock {
+            player: self.turn,
+            remaining: self.clock.remaining(self.turn)?,
+        })
+    }
+}
@@ -1,13 +1,24 @@
 use bevy::{render::color::Color, ui::Val};
 
-pub const MAP_WIDTH: f32 = 8.0;
-pub const MAP_HEIGHT: f32 = 4.0;
+pub const DEFAULT_MAP_WIDTH: usize = 16;
+pub const DEFAULT_MAP_HEIGHT: usize = 8;
 pub const TILE_SIZE: f32 = 50.0;
 pub const SCOREBOARD_FONT_SIZE: f32 = 40.0;
 pub const SCOREBOARD_TEXT_PADDING: Val = Val::Px(5.0);
 pub const SCOREBOARD_TEXT_PADDING_2: Val = Val::Px(55.0);
+pub const SCOREBOARD_LINE_HEIGHT: f32 = 50.0;
 pub const TEXT_COLOR: Color = Color::rgb(0.0, 0.0, 0.0);
 pub const SCORE_COLOR: Color = Color::rgb(0.0, 0.0, 0.0);
-pub const MAX_MOUNTAIN_COUNT: usize = 5;
-pub const MAX_WATER_COUNT: usize = 4;
-pub const GRID_SIZE: usize = (MAP_HEIGHT * MAP_WIDTH * 4.0 + 1.0) as usize;
\ No newline at end of file
+pub const TOAST_FONT_SIZE: f32 = 30.0;
+pub const TOAST_DURATION_SECS: f32 = 3.0;
+pub const RULES_FONT_SIZE: f32 = 18.0;
+pub const MAX_PLAYERS: usize = 4;
+/// Turns a score race lasts unless the server is told otherwise.
+pub const DEFAULT_TURN_LIMIT: usize = 40;
+pub const DEFAULT_VISION_RANGE: usize = 2;
+pub const MOUNTAIN_VISION_BONUS: usize = 1;
+pub const LAST_SEEN_TINT: Color = Color::rgb(0.6, 0.6, 0.6);
+pub const UNKNOWN_TINT: Color = Color::rgb(0.25, 0.25, 0.25);
+pub const HEALTH_PIP_SIZE: f32 = 6.0;
+pub const HEALTH_PIP_COLOR: Color = Color::rgb(0.2, 0.8, 0.2);
+pub const LOST_HEALTH_PIP_COLOR: Color = Color::rgb(0.3, 0.3, 0.3);
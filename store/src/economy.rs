@@ -0,0 +1,111 @@
+use crate::*;
+
+/// What every action costs, paid from the stockpile of the player taking it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CostTable {
+    /// Per tile hit.
+    pub attack: usize,
+    pub make_farm: usize,
+    pub make_tower: usize,
+    /// Per HP restored.
+    pub repair: usize,
+    /// Per mountain or water placed, clearing a tile again is free.
+    pub terrain: usize,
+}
+
+impl CostTable {
+    pub const DEFAULT: CostTable = CostTable {
+        attack: 1,
+        make_farm: 2,
+        make_tower: 4,
+        repair: 1,
+        terrain: 1,
+    };
+
+    /// What `action` costs. Upgrades are priced per level in the
+    /// [`LevelTable`] and repairs by the damage they undo instead, see
+    /// [`GameState::cost`].
+    pub fn cost(&self, action: &GameAction) -> usize {
+        match action {
+            GameAction::Attack(targets) => self.attack * targets.len(),
+            GameAction::MakeFarm(_) => self.make_farm,
+            GameAction::MakeTower(_) => self.make_tower,
+            GameAction::MakeTerrain(_, Terrain::None) => 0,
+            GameAction::MakeTerrain(..) => self.terrain,
+            GameAction::Upgrade(_)
+            | GameAction::Repair(_)
+            | GameAction::Select(_)
+            | GameAction::Deselect
+            | GameAction::SetTerrainMode(_)
+            | GameAction::EndTerrainPlacement
+            | GameAction::EndTurn
+            | GameAction::Timeout => 0,
+        }
+    }
+}
+
+impl Default for CostTable {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Every player's stockpile. The base and the farms pay into it at the start
+/// of their owner's turn, actions are paid out of it.
+#[derive(Resource, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Economy {
+    /// Indexed by `Player as usize`.
+    pub balance: Vec<usize>,
+}
+
+impl Default for Economy {
+    fn default() -> Self {
+        Self::new(2, &RuleSet::default())
+    }
+}
+
+impl Economy {
+    pub fn new(players: usize, rules: &RuleSet) -> Self {
+        Self {
+            balance: vec![rules.starting_resources; players],
+        }
+    }
+
+    pub fn balance(&self, player: Player) -> usize {
+        self.balance.get(player as usize).copied().unwrap_or(0)
+    }
+
+    /// What `player` collects per turn with the board as it is: the base's
+    /// share plus the farm income for every farm level.
+    pub fn income(rules: &RuleSet, grid: &TileGrid, player: Player) -> usize {
+        rules.base_income + rules.farm_income * Self::farm_levels(grid, player)
+    }
+
+    /// Levels of all of `player`'s farms added up.
+    pub fn farm_levels(grid: &TileGrid, player: Player) -> usize {
+        grid.get_tiles()
+            .filter_map(|tile| match *tile {
+                TileType::Occupied {
+                    player_tile: PlayerTile::Farm,
+                    owner,
+                    level,
+                    ..
+                } if owner == player => Some(level),
+                _ => None,
+            })
+            .sum()
+    }
+
+    pub fn collect(&mut self, rules: &RuleSet, grid: &TileGrid, player: Player) {
+        if let Some(balance) = self.balance.get_mut(player as usize) {
+            *balance += Self::income(rules, grid, player);
+        }
+    }
+
+    pub fn spend(&mut self, player: Player, amount: usize) {
+        if let Some(balance) = self.balance.get_mut(player as usize) {
+            *balance = balance.saturating_sub(amount);
+        }
+    }
+}
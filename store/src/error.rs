@@ -0,0 +1,46 @@
+use crate::*;
+
+/// Why [`GameState::get_action`] refused a [`TileEvent`]. Sent back to the
+/// offending client in [`ClientEvent::ActionRejected`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RuleError {
+    NotYourTurn,
+    WrongPhase,
+    OutOfBounds,
+    NotEnoughResources { needed: usize, available: usize },
+    TargetNotAdjacent,
+    TerrainLimitReached(Terrain),
+    TileOccupied,
+    MaxLevel,
+    NotDamaged,
+    NotSelectable,
+    InvalidInput,
+    GameOver,
+}
+
+impl std::fmt::Display for RuleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleError::NotYourTurn => write!(f, "It is not your turn"),
+            RuleError::WrongPhase => write!(f, "That can't be done in this phase"),
+            RuleError::OutOfBounds => write!(f, "That tile is outside the map"),
+            RuleError::NotEnoughResources { needed, available } => write!(
+                f,
+                "That costs {} resources but only {} are stockpiled",
+                needed, available
+            ),
+            RuleError::TargetNotAdjacent => write!(f, "That tile can't be reached from here"),
+            RuleError::TerrainLimitReached(terrain) => {
+                write!(f, "No {} placements left", terrain)
+            }
+            RuleError::TileOccupied => write!(f, "That tile is already occupied"),
+            RuleError::MaxLevel => write!(f, "That tile can't be upgraded any further"),
+            RuleError::NotDamaged => write!(f, "That tile isn't damaged"),
+            RuleError::NotSelectable => write!(f, "Only your own tiles can be selected"),
+            RuleError::InvalidInput => write!(f, "Nothing to do there"),
+            RuleError::GameOver => write!(f, "The game is over"),
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
@@ -16,23 +16,27 @@ pub enum GameEvent {
     },
 }
 
-#[derive(Event, Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Event, Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub enum TileEvent {
     GetUsername {
         username: String,
     },
     TileAction {
         client_id: u64,
-        position: Vec2,
+        position: GridPos,
         action: GameInput,
     },
     ToggleSelect {
         client_id: u64,
-        position: Vec2,
+        position: GridPos,
+    },
+    /// Hands the turn over before the action points run out.
+    EndTurn {
+        client_id: u64,
     },
     TerrainAction {
         client_id: u64,
-        position: Vec2,
+        position: GridPos,
         action: GameInput,
     },
     None,
@@ -43,7 +47,18 @@ impl TileEvent {
         matches!(self, TileEvent::None)
     }
 
-    pub fn new_action(client_id: u64, button: &MouseButton, position: Vec2) -> Self {
+    /// The client that sent a move, `None` for events that aren't one.
+    pub fn client_id(&self) -> Option<u64> {
+        match *self {
+            TileEvent::TileAction { client_id, .. }
+            | TileEvent::ToggleSelect { client_id, .. }
+            | TileEvent::EndTurn { client_id }
+            | TileEvent::TerrainAction { client_id, .. } => Some(client_id),
+            TileEvent::GetUsername { .. } | TileEvent::None => None,
+        }
+    }
+
+    pub fn new_action(client_id: u64, button: &MouseButton, position: GridPos) -> Self {
         TileEvent::TileAction {
             client_id,
             position,
@@ -53,7 +68,7 @@ impl TileEvent {
 
     pub fn from_input(
         client_id: u64,
-        position: Vec2,
+        position: GridPos,
         input: GameInput,
         state: &ClientState,
     ) -> Self {
@@ -68,6 +83,16 @@ impl TileEvent {
                 position,
                 action: GameInput::Mouse(button),
             },
+            GameInput::Keyboard(key @ (KeyCode::T | KeyCode::R)) if state == &ClientState::Game => {
+                TileEvent::TileAction {
+                    client_id,
+                    position,
+                    action: GameInput::Keyboard(key),
+                }
+            }
+            GameInput::Keyboard(KeyCode::Return) if state == &ClientState::Game => {
+                TileEvent::EndTurn { client_id }
+            }
             GameInput::Keyboard(KeyCode::Space) => TileEvent::ToggleSelect {
                 client_id,
                 position,
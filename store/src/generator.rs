@@ -0,0 +1,97 @@
+use bevy::utils::HashSet;
+
+use crate::*;
+
+/// Chance out of 100 that a free tile (and its mirror) becomes water.
+const WATER_CHANCE: u64 = 12;
+/// Chance out of 100 that a free tile (and its mirror) becomes a mountain.
+const MOUNTAIN_CHANCE: u64 = 10;
+
+/// SplitMix64, small enough to keep here so a seed means the same map on
+/// every platform and every version of our dependencies.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+/// Scatters water and mountains over `config`, keeping whatever the map
+/// already places. Terrain is mirrored through the centre of the board so
+/// opposite bases see the same surroundings, and water is taken back out
+/// until every base can reach the first one. The same seed and map always
+/// give the same result. On hex boards the mirror only keeps neighbours
+/// together when the height is even, odd rows being shifted.
+pub fn generate_terrain(config: &MapConfig, seed: u64) -> MapConfig {
+    let mut rng = Rng(seed);
+    let mut generated = config.clone();
+
+    let min = grid_origin(config.width, config.height);
+    let max = min + GridPos(config.width as i32 - 1, config.height as i32 - 1);
+    let mirror = |p: GridPos| min + max - p;
+
+    // Bases keep their neighbours free so nobody starts walled in
+    let reserved = config
+        .bases
+        .iter()
+        .flat_map(|&base| config.topology.neighbours(base))
+        .chain(config.bases.iter().copied())
+        .collect::<HashSet<_>>();
+    let is_free = |p: GridPos| !reserved.contains(&p) && !config.is_preplaced(p);
+
+    let mut water_pairs = Vec::new();
+    for y in min.1..=max.1 {
+        for x in min.0..=max.0 {
+            let position = GridPos(x, y);
+            let mirrored = mirror(position);
+
+            // Visit each pair once, from its lower half
+            if (position.1, position.0) > (mirrored.1, mirrored.0)
+                || !is_free(position)
+                || !is_free(mirrored)
+            {
+                continue;
+            }
+
+            let pair = if position == mirrored {
+                vec![position]
+            } else {
+                vec![position, mirrored]
+            };
+
+            match rng.below(100) {
+                roll if roll < WATER_CHANCE => {
+                    generated.water.extend(&pair);
+                    water_pairs.push(pair);
+                }
+                roll if roll < WATER_CHANCE + MOUNTAIN_CHANCE => generated.mountains.extend(&pair),
+                _ => (),
+            }
+        }
+    }
+
+    while !water_pairs.is_empty()
+        && matches!(generated.validate(), Err(MapError::BaseUnreachable(_)))
+    {
+        let pair = water_pairs.swap_remove(rng.below(water_pairs.len() as u64) as usize);
+        generated.water.retain(|p| !pair.contains(p));
+    }
+
+    generated
+}
+
+impl TileGrid {
+    /// A board for `config` with terrain from [`generate_terrain`].
+    pub fn generate(config: &MapConfig, rules: &RuleSet, seed: u64) -> Self {
+        Self::new(&generate_terrain(config, seed), rules)
+    }
+}
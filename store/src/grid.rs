@@ -1,80 +1,85 @@
 use bevy::utils::HashSet;
+use fixedbitset::FixedBitSet;
 use std::collections::VecDeque;
 
 use crate::*;
 
-pub const ADYACENCIES: [(f32, f32); 4] = [(1.0, 0.0), (-1.0, 0.0), (0.0, 1.0), (0.0, -1.0)];
-
-#[derive(Resource, Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TileGrid {
-    pub grid: [TileType; (MAP_WIDTH * MAP_HEIGHT * 4.0 + 1.0) as usize],
-}
-
-impl Serialize for TileGrid {
-    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        let mut grid = [TileType::EMPTY; GRID_SIZE];
-
-        for (i, tile) in self.grid.iter().enumerate() {
-            grid[i] = *tile;
-        }
-
-        grid.serialize(serializer)
-    }
+    pub width: usize,
+    pub height: usize,
+    pub topology: Topology,
+    /// Row-major tiles plus one trailing sentinel that out of bounds lookups
+    /// resolve to.
+    pub grid: Vec<TileType>,
 }
 
-impl<'de> Deserialize<'de> for TileGrid {
-    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        let vec: Vec<TileType> = Vec::deserialize(deserializer)?;
-
-        let mut grid = [TileType::EMPTY; GRID_SIZE];
-        for (place, element) in grid.iter_mut().zip(vec.iter()) {
-            *place = *element;
-        }
-
-        Ok(Self { grid })
+impl Default for TileGrid {
+    fn default() -> Self {
+        Self::new(&MapConfig::default(), &RuleSet::default())
     }
 }
 
-impl Default for TileGrid {
-    fn default() -> Self {
-        let mut def = Self {
-            grid: [TileType::EMPTY; GRID_SIZE],
+impl TileGrid {
+    pub fn new(config: &MapConfig, rules: &RuleSet) -> Self {
+        let mut grid = Self {
+            width: config.width,
+            height: config.height,
+            topology: config.topology,
+            grid: vec![TileType::EMPTY; config.width * config.height + 1],
         };
 
-        def.make_base(Player::Red);
-        def.make_base(Player::Blue);
+        for &position in &config.mountains {
+            grid.set_tile(position, TileType::Empty(Terrain::Mountain));
+        }
+        for &position in &config.water {
+            grid.set_tile(position, TileType::WATER);
+        }
+        for &position in &config.neutral {
+            grid.set_tile(
+                position,
+                TileType::Neutral {
+                    hp: rules.neutral_tile_hp,
+                },
+            );
+        }
+        for (i, &base) in config.bases.iter().enumerate() {
+            grid.make_base(base, Player::from(i), rules);
+        }
 
-        def
+        grid
     }
-}
 
-impl TileGrid {
     pub fn get_tiles(&self) -> impl Iterator<Item = &TileType> {
         self.grid.iter().take(self.grid.len() - 1)
     }
 
     pub fn update(&mut self) -> Vec<TileChange> {
-        // Remove tiles disconnected from base
+        // Remove tiles disconnected from base, flooding out from each owner's
+        // base once rather than searching for it from every tile
+        let mut reached: [Option<FixedBitSet>; MAX_PLAYERS] = Default::default();
         let mut to_remove = Vec::new();
 
         for (i, tile) in self.get_tiles().enumerate() {
             if let TileType::Occupied {
-                player_tile: PlayerTile::Farm | PlayerTile::Tile,
+                player_tile: PlayerTile::Farm | PlayerTile::Tile | PlayerTile::Tower,
                 owner,
                 ..
-            } = tile
+            } = *tile
             {
-                if !self.is_connected_to_base(get_vec_from_index(i), *owner) {
+                let reached =
+                    reached[owner as usize].get_or_insert_with(|| self.reachable_from_base(owner));
+                if !reached.contains(i) {
                     to_remove.push(i);
                 }
             }
         }
 
-        println!(
+        debug!(
             "Removing: {:?}",
             to_remove
                 .iter()
-                .map(|&i| get_vec_from_index(i))
+                .map(|&i| self.get_pos_from_index(i))
                 .collect::<Vec<_>>()
         );
 
@@ -83,7 +88,7 @@ impl TileGrid {
         for i in to_remove {
             self.grid[i] = TileType::EMPTY;
             changes.push(TileChange {
-                position: get_vec_from_index(i),
+                position: self.get_pos_from_index(i),
                 tile: TileType::EMPTY,
             });
         }
@@ -91,85 +96,119 @@ impl TileGrid {
         changes
     }
 
-    pub fn check_win(&self) -> [bool; 2] {
-        // Count the amount of tiles each player has and if 0 then the other player wins
-        let mut counts = [0, 0];
-
-        for tile in self.get_tiles() {
-            if let TileType::Occupied { owner, .. } = tile {
-                counts[*owner as usize] += 1;
-            }
+    /// Why `player` is out of the game, once they have lost their base or every
+    /// one of their tiles. Only tells the two apart before [`Self::update`]
+    /// has cleared the tiles cut off from a lost base.
+    pub fn check_defeat(&self, player: Player) -> Option<GameOverReason> {
+        if !self.get_tiles().any(|tile| tile.owner() == Some(player)) {
+            Some(GameOverReason::NoTilesLeft)
+        } else if !self.get_tiles().any(|tile| tile.is_base(player)) {
+            Some(GameOverReason::BaseDestroyed)
+        } else {
+            None
         }
+    }
 
-        [counts[0] == 0, counts[1] == 0]
+    /// Every tile of `player` within `reach` of a tower at `position`, see
+    /// [`RuleSet::tower_reach`]. Bases are too sturdy to be worn down.
+    pub fn tower_targets(&self, position: GridPos, reach: usize, player: Player) -> Vec<GridPos> {
+        self.topology
+            .within(position, reach)
+            .filter(|&target| {
+                let tile = self.get_tile(target);
+                tile.owner() == Some(player) && !tile.is_base(player)
+            })
+            .collect()
     }
 
-    pub fn in_bounds(x: f32, y: f32) -> bool {
-        x >= -MAP_WIDTH && y >= -MAP_HEIGHT && x < MAP_WIDTH && y < MAP_HEIGHT
+    pub fn in_bounds(&self, x: i32, y: i32) -> bool {
+        let GridPos(min_x, min_y) = self.origin();
+
+        x >= min_x && y >= min_y && x < min_x + self.width as i32 && y < min_y + self.height as i32
     }
 
-    pub fn in_bounds_index(index: &Vec2) -> bool {
-        Self::in_bounds(index.x, index.y)
+    pub fn in_bounds_index(&self, index: &GridPos) -> bool {
+        self.in_bounds(index.0, index.1)
     }
 
-    pub fn get_index(Vec2 { x, y }: Vec2) -> usize {
-        if !Self::in_bounds(x, y) {
-            return GRID_SIZE - 1;
+    /// Lowest coordinate on the board, see [`grid_origin`].
+    pub fn origin(&self) -> GridPos {
+        grid_origin(self.width, self.height)
+    }
+
+    pub fn get_index(&self, GridPos(x, y): GridPos) -> usize {
+        if !self.in_bounds(x, y) {
+            return self.sentinel();
         }
 
-        (y + MAP_HEIGHT) as usize * (MAP_WIDTH as usize * 2) + (x + MAP_WIDTH) as usize
+        let GridPos(min_x, min_y) = self.origin();
+        (y - min_y) as usize * self.width + (x - min_x) as usize
+    }
+
+    pub fn get_pos_from_index(&self, index: usize) -> GridPos {
+        self.origin() + GridPos((index % self.width) as i32, (index / self.width) as i32)
+    }
+
+    pub fn get_index_from_position(&self, pos: &Position) -> usize {
+        self.get_index(self.topology.grid_position(pos.0))
     }
 
-    pub fn get_index_from_position(pos: &Position) -> usize {
-        Self::get_index(pos.as_grid_index())
+    fn sentinel(&self) -> usize {
+        self.grid.len() - 1
     }
 
-    pub fn get_tile(&self, index: Vec2) -> TileType {
-        self.grid[Self::get_index(index)]
+    pub fn get_tile(&self, index: GridPos) -> TileType {
+        self.grid[self.get_index(index)]
     }
 
-    pub fn get_tile_mut(&mut self, index: Vec2) -> &mut TileType {
-        let idx = Self::get_index(index);
+    pub fn get_tile_mut(&mut self, index: GridPos) -> &mut TileType {
+        let idx = self.get_index(index);
 
         &mut self.grid[idx]
     }
 
-    pub fn empty(&mut self, index: Vec2) {
-        self.grid[Self::get_index(index)] = TileType::EMPTY;
-    }
+    pub fn empty(&mut self, index: GridPos) {
+        let idx = self.get_index(index);
 
-    pub fn upgrade(&mut self, index: Vec2) {
-        self.grid[Self::get_index(index)].upgrade();
+        self.grid[idx] = TileType::EMPTY;
     }
 
-    pub fn get_tile_tup(&self, (x, y): (i32, i32)) -> TileType {
-        self.grid[Self::get_index(Vec2::new(x as f32, y as f32))]
+    pub fn upgrade(&mut self, index: GridPos, levels: &LevelTable) {
+        let idx = self.get_index(index);
+
+        self.grid[idx].upgrade(levels);
     }
 
-    pub fn set_tile(&mut self, index: Vec2, tile: TileType) {
-        let idx = Self::get_index(index);
+    pub fn set_tile(&mut self, index: GridPos, tile: TileType) {
+        let idx = self.get_index(index);
 
-        if idx != GRID_SIZE - 1 {
+        if idx != self.sentinel() {
             self.grid[idx] = tile;
         }
     }
 
-    pub fn capture(&mut self, index: Vec2, player: Player) {
-        let idx = Self::get_index(index);
+    pub fn capture(&mut self, index: GridPos, player: Player, rules: &RuleSet) {
+        let idx = self.get_index(index);
 
-        if idx != GRID_SIZE - 1 {
+        if idx != self.sentinel() {
             if let TileType::Occupied {
                 player_tile,
                 terrain,
+                owner,
                 ..
             } = self.grid[idx]
             {
+                // A taken base is only a foothold, bases can't change hands
+                let player_tile = match player_tile {
+                    PlayerTile::Base if owner != player => PlayerTile::Tile,
+                    player_tile => player_tile,
+                };
                 self.grid[idx] = TileType::Occupied {
                     player_tile,
                     terrain,
                     owner: player,
                     level: 1,
-                    hp: terrain.get_health(),
+                    hp: rules.terrain_hp.get(terrain),
                 };
             } else if let TileType::Empty(terrain) = self.grid[idx] {
                 self.grid[idx] = TileType::Occupied {
@@ -177,63 +216,88 @@ impl TileGrid {
                     terrain,
                     owner: player,
                     level: 1,
-                    hp: terrain.get_health(),
+                    hp: rules.terrain_hp.get(terrain),
+                };
+            } else if let TileType::Neutral { .. } = self.grid[idx] {
+                self.grid[idx] = TileType::Occupied {
+                    player_tile: PlayerTile::Tile,
+                    terrain: Terrain::None,
+                    owner: player,
+                    level: 1,
+                    hp: rules.terrain_hp.none,
                 };
             }
         }
     }
 
-    pub fn make_base(&mut self, owner: Player) {
-        self.grid[Self::get_index(match owner {
-            Player::Red => Vec2::new(-MAP_WIDTH, -MAP_HEIGHT),
-            Player::Blue => Vec2::new(MAP_WIDTH - 1.0, MAP_HEIGHT - 1.0),
-        })] = TileType::Occupied {
-            player_tile: PlayerTile::Base,
-            terrain: Terrain::None,
-            owner,
-            level: 1,
-            hp: 2,
-        };
+    pub fn make_base(&mut self, position: GridPos, owner: Player, rules: &RuleSet) {
+        self.set_tile(
+            position,
+            TileType::Occupied {
+                player_tile: PlayerTile::Base,
+                terrain: Terrain::None,
+                owner,
+                level: 1,
+                hp: rules.base_hp,
+            },
+        );
     }
 
-    pub fn get_connected_tiles(&self, pos: Vec2, owner: Player) -> Vec<Vec2> {
-        ADYACENCIES
-            .iter()
-            .filter_map(|&(dx, dy)| {
-                let next_pos = pos + Vec2::new(dx, dy);
-                self.get_tile(next_pos)
-                    .owner()
-                    .filter(|&p| p == owner)
-                    .map(|_| next_pos)
-            })
+    pub fn get_connected_tiles(&self, pos: GridPos, owner: Player) -> Vec<GridPos> {
+        self.topology
+            .neighbours(pos)
+            .filter(|&next_pos| self.get_tile(next_pos).owner() == Some(owner))
             .collect()
     }
 
-    pub fn get_any_connected(&self, pos: Vec2, owner: Player) -> Option<Vec2> {
+    pub fn get_any_connected(&self, pos: GridPos, owner: Player) -> Option<GridPos> {
         self.get_connected_tiles(pos, owner).first().copied()
     }
 
-    pub fn is_connected_to_base(&self, start: Vec2, player: Player) -> bool {
+    /// Indices of every tile joined to `player`'s base through their own
+    /// tiles, the base included.
+    pub fn reachable_from_base(&self, player: Player) -> FixedBitSet {
+        let mut reached = FixedBitSet::with_capacity(self.grid.len());
+        let mut stack = Vec::new();
+
+        for (i, tile) in self.get_tiles().enumerate() {
+            if tile.is_base(player) {
+                reached.insert(i);
+                stack.push(self.get_pos_from_index(i));
+            }
+        }
+
+        while let Some(pos) = stack.pop() {
+            for next_pos in self.topology.neighbours(pos) {
+                let i = self.get_index(next_pos);
+                if !reached.contains(i) && self.grid[i].owner() == Some(player) {
+                    reached.insert(i);
+                    stack.push(next_pos);
+                }
+            }
+        }
+
+        reached
+    }
+
+    /// Searches from `start` for `player`'s base. [`Self::update`] floods
+    /// out from the base with [`Self::reachable_from_base`] instead, which is
+    /// cheaper when checking many tiles at once.
+    pub fn is_connected_to_base(&self, start: GridPos, player: Player) -> bool {
         let mut queue = VecDeque::new();
         let mut visited = HashSet::new();
 
-        let start_tuple = (start.x as i32, start.y as i32);
-        queue.push_back(start_tuple);
-        visited.insert(start_tuple);
+        queue.push_back(start);
+        visited.insert(start);
 
-        while let Some((x, y)) = queue.pop_front() {
-            if self.get_tile_tup((x, y)).is_base(player) {
+        while let Some(pos) = queue.pop_front() {
+            if self.get_tile(pos).is_base(player) {
                 return true;
             }
 
-            for &dir in &[(0, 1), (0, -1), (1, 0), (-1, 0)] {
-                let next_pos = (x + dir.0, y + dir.1);
-
+            for next_pos in self.topology.neighbours(pos) {
                 if !visited.contains(&next_pos)
-                    && self
-                        .get_tile_tup(next_pos)
-                        .owner()
-                        .is_some_and(|p| p == player)
+                    && self.get_tile(next_pos).owner().is_some_and(|p| p == player)
                 {
                     queue.push_back(next_pos);
                     visited.insert(next_pos);
@@ -244,10 +308,3 @@ impl TileGrid {
         false
     }
 }
-
-pub fn get_vec_from_index(index: usize) -> Vec2 {
-    Vec2::new(
-        (index % (MAP_WIDTH as usize * 2)) as f32 - MAP_WIDTH,
-        (index / (MAP_WIDTH as usize * 2)) as f32 - MAP_HEIGHT,
-    )
-}
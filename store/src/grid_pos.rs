@@ -0,0 +1,79 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::*;
+
+/// Integer coordinates of a cell on the board. This is what the rules engine
+/// and the wire protocol use; world space [`Position`]s only exist on the
+/// client.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GridPos(pub i32, pub i32);
+
+impl GridPos {
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self(x, y)
+    }
+
+    pub fn x(&self) -> i32 {
+        self.0
+    }
+
+    pub fn y(&self) -> i32 {
+        self.1
+    }
+
+    /// Unit step along each axis, e.g. the direction of an orthogonal attack.
+    pub fn signum(self) -> Self {
+        Self(self.0.signum(), self.1.signum())
+    }
+
+    /// The same vector turned a quarter turn counter-clockwise.
+    pub fn rotate_ccw(self) -> Self {
+        Self(-self.1, self.0)
+    }
+}
+
+impl Add for GridPos {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl Sub for GridPos {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0, self.1 - rhs.1)
+    }
+}
+
+impl Mul<i32> for GridPos {
+    type Output = Self;
+
+    fn mul(self, rhs: i32) -> Self {
+        Self(self.0 * rhs, self.1 * rhs)
+    }
+}
+
+impl Neg for GridPos {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0, -self.1)
+    }
+}
+
+impl std::fmt::Display for GridPos {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.0, self.1)
+    }
+}
+
+/// Where the cell is drawn on a square board, see
+/// [`Topology::world_position`] for the others.
+impl From<GridPos> for Position {
+    fn from(position: GridPos) -> Self {
+        Topology::Square.world_position(position)
+    }
+}
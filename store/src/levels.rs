@@ -0,0 +1,108 @@
+use crate::*;
+
+/// One level of a tile kind.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Level {
+    /// HP at this level on flat ground. Upgrading adds the difference to the
+    /// previous level, so damage taken and terrain bonuses carry over.
+    pub hp: usize,
+    /// What upgrading to this level costs. Unused for the first level, that
+    /// one is built or captured.
+    pub upgrade_cost: usize,
+    /// Tiles hit by an attack from this level, written for an attack towards
+    /// `(1, 0)` and turned to face the target, see [`Topology::facing`]. Only
+    /// read for [`PlayerTile::Tile`]s.
+    #[serde(default)]
+    pub attack: Vec<GridPos>,
+}
+
+/// Every level each kind of tile can reach, the first entry being level 1.
+/// How many entries there are is the kind's max level.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LevelTable {
+    pub tile: Vec<Level>,
+    pub farm: Vec<Level>,
+    pub tower: Vec<Level>,
+}
+
+impl Default for LevelTable {
+    fn default() -> Self {
+        let level = |hp, upgrade_cost, attack: &[(i32, i32)]| Level {
+            hp,
+            upgrade_cost,
+            attack: attack.iter().map(|&(x, y)| GridPos(x, y)).collect(),
+        };
+
+        Self {
+            tile: vec![
+                // The clicked tile
+                level(1, 0, &[(1, 0)]),
+                // Two in a line
+                level(2, 2, &[(1, 0), (2, 0)]),
+                // The tile in front and both of its neighbours
+                level(3, 2, &[(1, 0), (1, -1), (1, 1)]),
+                // Two in a line, spreading out at the first
+                level(4, 3, &[(1, 0), (2, 0), (1, -1), (1, 1)]),
+            ],
+            farm: vec![level(1, 0, &[]), level(2, 3, &[]), level(3, 3, &[])],
+            tower: vec![level(2, 0, &[]), level(3, 3, &[]), level(4, 3, &[])],
+        }
+    }
+}
+
+impl LevelTable {
+    /// The levels of `player_tile`, bases only have the one.
+    pub fn levels(&self, player_tile: PlayerTile) -> &[Level] {
+        match player_tile {
+            PlayerTile::Tile => &self.tile,
+            PlayerTile::Farm => &self.farm,
+            PlayerTile::Tower => &self.tower,
+            PlayerTile::Base => &[],
+        }
+    }
+
+    pub fn get(&self, player_tile: PlayerTile, level: usize) -> Option<&Level> {
+        self.levels(player_tile).get(level.checked_sub(1)?)
+    }
+
+    pub fn max_level(&self, player_tile: PlayerTile) -> usize {
+        self.levels(player_tile).len().max(1)
+    }
+
+    /// What upgrading `tile` costs, or `None` if it can't be upgraded.
+    pub fn upgrade_cost(&self, tile: TileType) -> Option<usize> {
+        let next = self.get(tile.player_tile()?, tile.level()? + 1)?;
+        Some(next.upgrade_cost)
+    }
+
+    /// The attack shape of a tile at `level`, or `None` if it can't attack.
+    pub fn attack(&self, level: usize) -> Option<&[GridPos]> {
+        let attack = &self.get(PlayerTile::Tile, level)?.attack;
+        (!attack.is_empty()).then_some(attack.as_slice())
+    }
+
+    /// `tile` one level up, or `None` if it is at its max level already.
+    pub fn upgraded(&self, tile: TileType) -> Option<TileType> {
+        let TileType::Occupied {
+            player_tile,
+            terrain,
+            owner,
+            level,
+            hp,
+        } = tile
+        else {
+            return None;
+        };
+        let current = self.get(player_tile, level)?;
+        let next = self.get(player_tile, level + 1)?;
+
+        Some(TileType::Occupied {
+            player_tile,
+            terrain,
+            owner,
+            level: level + 1,
+            hp: (hp + next.hp).saturating_sub(current.hp),
+        })
+    }
+}
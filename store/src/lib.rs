@@ -4,23 +4,49 @@ use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 use renetcode::NETCODE_USER_DATA_BYTES;
 
+pub use ai::*;
+pub use clock::*;
 pub use consts::*;
+pub use economy::*;
+pub use error::*;
 pub use events::*;
-pub use farms::*;
+pub use generator::*;
 pub use grid::*;
+pub use grid_pos::*;
+pub use levels::*;
+pub use map::*;
+pub use mode::*;
 pub use player::*;
+pub use replay::*;
+pub use rules::*;
+pub use save::*;
 pub use state::*;
 pub use tiles::*;
 pub use terrain::*;
+pub use topology::*;
+pub use vision::*;
 
+mod ai;
+mod clock;
 mod consts;
+mod economy;
+mod error;
 mod events;
-mod farms;
+mod generator;
 mod grid;
+mod grid_pos;
+mod levels;
+mod map;
+mod mode;
 mod player;
+mod replay;
+mod rules;
+mod save;
 mod state;
 mod tiles;
 mod terrain;
+mod topology;
+mod vision;
 
 pub const SERVER_ADDR: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 9000);
 
@@ -29,16 +55,18 @@ pub enum PlayerTile {
     Farm,
     Tile,
     Base,
+    /// Hits the tiles of whoever just moved, see [`GameState::fire_towers`].
+    Tower,
 }
 
-#[derive(Resource, Debug, Default)]
+#[derive(Resource, Debug, Default, Clone, Serialize, Deserialize)]
 pub struct AttackController {
-    pub selected: Option<Vec2>,
+    pub selected: Option<GridPos>,
     pub selected_level: Option<usize>,
 }
 
 impl AttackController {
-    pub fn select(&mut self, position: Vec2, level: usize) {
+    pub fn select(&mut self, position: GridPos, level: usize) {
         self.selected = Some(position);
         self.selected_level = Some(level);
     }
@@ -53,8 +81,10 @@ impl AttackController {
 pub struct Position(pub Vec2);
 
 impl Position {
-    pub fn as_grid_index(&self) -> Vec2 {
-        (self.0 - TILE_SIZE / 2.0) / TILE_SIZE
+    /// The cell under this position on a square board, see
+    /// [`Topology::grid_position`] for the others.
+    pub fn as_grid_index(&self) -> GridPos {
+        Topology::Square.grid_position(self.0)
     }
 }
 
@@ -66,14 +96,39 @@ impl From<Position> for Transform {
 
 #[derive(Event, Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum ClientEvent {
-    Init(Box<TileGrid>),
+    Init(MapConfig, Box<TileGrid>, Box<RuleSet>),
     TileChanges(Vec<TileChange>),
-    Select(Vec2),
+    Select(GridPos),
     Deselect,
     Turn(Player),
     TerrainMode(Terrain),
-    Farms([usize; 2]),
+    /// Action points the player on turn has left.
+    ActionPoints(usize),
+    /// Every seat's stockpile and what it collects per turn, indexed by
+    /// `Player as usize`. `None` where fog of war hides another player's
+    /// economy.
+    Resources {
+        balance: Vec<Option<usize>>,
+        income: Vec<Option<usize>>,
+    },
     GamePhase(ClientState),
+    ActionRejected(RuleError),
+    Eliminated {
+        player: Player,
+        reason: GameOverReason,
+    },
+    GameOver {
+        winner: Player,
+        reason: GameOverReason,
+    },
+    /// What the receiving player can see, after every board update when
+    /// playing with fog of war.
+    Vision(Vision),
+    /// Sent every second while the match is timed.
+    Clock {
+        player: Player,
+        remaining: std::time::Duration,
+    },
 }
 
 #[derive(Component, Clone, Debug, Default, Serialize, Deserialize)]
@@ -87,16 +142,6 @@ pub enum Terrain {
     Mountain,
 }
 
-impl Terrain {
-    pub fn get_health(&self) -> usize {
-        match self {
-            Terrain::Water => 0,
-            Terrain::None => 1,
-            Terrain::Mountain => 2,
-        }
-    }
-}
-
 impl std::fmt::Display for Terrain {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -114,6 +159,7 @@ pub enum ClientState {
     Lobby,
     Terrain,
     Game,
+    Results,
 }
 
 pub struct Username(pub String);
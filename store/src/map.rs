@@ -0,0 +1,199 @@
+use std::{collections::VecDeque, fs, path::Path};
+
+use anyhow::Context;
+use bevy::utils::HashSet;
+
+use crate::*;
+
+/// Dimensions and starting layout of a board. Chosen by the server when the
+/// match is created and announced to clients in [`ClientEvent::Init`].
+///
+/// Hand-authored maps are this struct as JSON, e.g.
+/// `{"width": 8, "height": 4, "bases": [[-4, -2], [3, 1]], "water": [[0, 0]]}`.
+/// Everything but the dimensions and bases is optional, `"topology": "hex"`
+/// plays the map on hexes.
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MapConfig {
+    pub width: usize,
+    pub height: usize,
+    #[serde(default)]
+    pub topology: Topology,
+    /// Base position of every player, indexed by `Player as usize`.
+    pub bases: Vec<GridPos>,
+    #[serde(default)]
+    pub mountains: Vec<GridPos>,
+    #[serde(default)]
+    pub water: Vec<GridPos>,
+    /// Ownerless tiles anyone can capture.
+    #[serde(default)]
+    pub neutral: Vec<GridPos>,
+    /// Mountains each player may place during terrain placement, replacing
+    /// the [`RuleSet`]'s budget for this map.
+    #[serde(default)]
+    pub mountain_budget: Option<usize>,
+    /// Water tiles each player may place during terrain placement, replacing
+    /// the [`RuleSet`]'s budget for this map.
+    #[serde(default)]
+    pub water_budget: Option<usize>,
+}
+
+/// Why a map file was refused by [`MapConfig::validate`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MapError {
+    Empty,
+    PlayerCount(usize),
+    OutOfBounds(GridPos),
+    Overlapping(GridPos),
+    BaseUnreachable(Player),
+}
+
+impl std::fmt::Display for MapError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MapError::Empty => write!(f, "The map has no tiles"),
+            MapError::PlayerCount(count) => write!(
+                f,
+                "The map has {} bases but must have between 2 and {}",
+                count, MAX_PLAYERS
+            ),
+            MapError::OutOfBounds(position) => write!(f, "{} is outside the map", position),
+            MapError::Overlapping(position) => {
+                write!(f, "{} is used by more than one feature", position)
+            }
+            MapError::BaseUnreachable(player) => {
+                write!(f, "The {} base can't be reached over land", player)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MapError {}
+
+impl Default for MapConfig {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAP_WIDTH, DEFAULT_MAP_HEIGHT)
+    }
+}
+
+impl MapConfig {
+    /// A `width` x `height` board with two bases in opposite corners.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::with_players(width, height, 2)
+    }
+
+    /// A `width` x `height` board for `players` players. Bases are placed in
+    /// the corners, the first two diagonally opposite each other.
+    pub fn with_players(width: usize, height: usize, players: usize) -> Self {
+        assert!(
+            (2..=MAX_PLAYERS).contains(&players),
+            "Invalid player count: {}",
+            players
+        );
+
+        let min = grid_origin(width, height);
+        let max = min + GridPos(width as i32 - 1, height as i32 - 1);
+        let corners = [min, max, GridPos(min.0, max.1), GridPos(max.0, min.1)];
+
+        Self {
+            width,
+            height,
+            topology: Topology::default(),
+            bases: corners[..players].to_vec(),
+            mountains: Vec::new(),
+            water: Vec::new(),
+            neutral: Vec::new(),
+            mountain_budget: None,
+            water_budget: None,
+        }
+    }
+
+    /// Reads and validates a JSON map file.
+    pub fn load_from_path(path: &Path) -> anyhow::Result<Self> {
+        let json =
+            fs::read_to_string(path).with_context(|| format!("can't open {}", path.display()))?;
+        let config: Self = serde_json::from_str(&json).context("invalid map file")?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Checks that every feature is on the board, that no two share a tile
+    /// and that every base can be reached from the first one without
+    /// crossing water.
+    pub fn validate(&self) -> Result<(), MapError> {
+        if self.width == 0 || self.height == 0 {
+            return Err(MapError::Empty);
+        }
+        if !(2..=MAX_PLAYERS).contains(&self.player_count()) {
+            return Err(MapError::PlayerCount(self.player_count()));
+        }
+
+        let min = grid_origin(self.width, self.height);
+        let in_bounds = |p: GridPos| {
+            (min.0..min.0 + self.width as i32).contains(&p.0)
+                && (min.1..min.1 + self.height as i32).contains(&p.1)
+        };
+
+        let mut used = HashSet::new();
+        for &position in self
+            .bases
+            .iter()
+            .chain(&self.mountains)
+            .chain(&self.water)
+            .chain(&self.neutral)
+        {
+            if !in_bounds(position) {
+                return Err(MapError::OutOfBounds(position));
+            }
+            if !used.insert(position) {
+                return Err(MapError::Overlapping(position));
+            }
+        }
+
+        let mut reached = HashSet::new();
+        let mut queue = VecDeque::from([self.bases[0]]);
+        reached.insert(self.bases[0]);
+        while let Some(position) = queue.pop_front() {
+            for next in self.topology.neighbours(position) {
+                if in_bounds(next) && !self.water.contains(&next) && reached.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let unreachable = self
+            .players()
+            .find(|&p| !reached.contains(&self.bases[p as usize]));
+        match unreachable {
+            Some(player) => Err(MapError::BaseUnreachable(player)),
+            None => Ok(()),
+        }
+    }
+
+    pub fn player_count(&self) -> usize {
+        self.bases.len()
+    }
+
+    /// Every seat on this map in turn order.
+    pub fn players(&self) -> impl Iterator<Item = Player> {
+        (0..self.player_count()).map(Player::from)
+    }
+
+    pub fn base(&self, player: Player) -> Option<GridPos> {
+        self.bases.get(player as usize).copied()
+    }
+
+    /// Whether the map itself put something on `position`, which players
+    /// can't change during terrain placement.
+    pub fn is_preplaced(&self, position: GridPos) -> bool {
+        [&self.mountains, &self.water, &self.neutral]
+            .iter()
+            .any(|tiles| tiles.contains(&position))
+    }
+}
+
+/// Lowest coordinate of a `width` x `height` board. Boards are centred on the
+/// origin so the client can draw them without moving the camera.
+pub fn grid_origin(width: usize, height: usize) -> GridPos {
+    GridPos(-((width / 2) as i32), -((height / 2) as i32))
+}
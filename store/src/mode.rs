@@ -0,0 +1,198 @@
+use strum::{Display, EnumString};
+
+use crate::*;
+
+/// Variant rules layered over the core ones in [`GameState`]. Every hook
+/// leaves the core rules alone unless overridden, [`Classic`] is nothing but
+/// those defaults.
+pub trait GameMode {
+    /// Prepares a new match, once, before anyone has moved.
+    fn setup(&self, _state: &mut GameState) {}
+
+    /// Refuses actions the core rules accept but the mode doesn't, asked by
+    /// [`GameState::get_action`].
+    fn validate(&self, _state: &GameState, _action: &GameAction) -> Result<(), RuleError> {
+        Ok(())
+    }
+
+    /// Runs after every action [`GameState::consume`] plays, returning what
+    /// clients should hear about its effects.
+    fn after_action(&self, _state: &mut GameState, _action: &GameAction) -> Vec<ClientEvent> {
+        Vec::new()
+    }
+
+    /// Whoever has won by the mode's own terms, asked every time the turn is
+    /// handed over in the game phase. Knocking everyone else out wins in any
+    /// mode.
+    fn check_victory(&self, _state: &GameState) -> Option<(Player, GameOverReason)> {
+        None
+    }
+}
+
+/// Which [`GameMode`] a match is played in, picked by the server when the
+/// match is created.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, EnumString, Display, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Mode {
+    #[default]
+    Classic,
+    KingOfTheHill,
+    /// Parsed from a string with `turns` left at 0, which
+    /// [`GameState::set_mode`] takes as [`DEFAULT_TURN_LIMIT`].
+    ScoreRace {
+        turns: usize,
+    },
+    CaptureAllFarms,
+}
+
+impl Mode {
+    /// The rules of this mode. Modes keep no state of their own, whatever
+    /// they need to know is in the [`GameState`] they are handed.
+    pub fn game_mode(self) -> &'static dyn GameMode {
+        match self {
+            Mode::Classic => &Classic,
+            Mode::KingOfTheHill => &KingOfTheHill,
+            Mode::ScoreRace { .. } => &ScoreRace,
+            Mode::CaptureAllFarms => &CaptureAllFarms,
+        }
+    }
+}
+
+/// Knock everyone else out to win.
+pub struct Classic;
+
+impl GameMode for Classic {}
+
+/// Capture the neutral hill in the middle of the board and still hold it
+/// when your next turn comes round to win.
+pub struct KingOfTheHill;
+
+impl KingOfTheHill {
+    /// Where the hill stands on `config`: the middle of the board, see
+    /// [`grid_origin`], or the closest cell to it that the map leaves free.
+    /// `None` if the map leaves no cell free at all.
+    pub fn hill(config: &MapConfig) -> Option<GridPos> {
+        let origin = grid_origin(config.width, config.height);
+        (0..config.height as i32)
+            .flat_map(|y| (0..config.width as i32).map(move |x| origin + GridPos(x, y)))
+            .filter(|&position| !config.bases.contains(&position) && !config.is_preplaced(position))
+            .min_by_key(|&position| config.topology.distance(position, GridPos(0, 0)))
+    }
+}
+
+impl GameMode for KingOfTheHill {
+    fn setup(&self, state: &mut GameState) {
+        if let Some(hill) = Self::hill(&state.map_config) {
+            let hp = state.rules.neutral_tile_hp;
+            state.grid.set_tile(hill, TileType::Neutral { hp });
+        }
+    }
+
+    fn validate(&self, state: &GameState, action: &GameAction) -> Result<(), RuleError> {
+        match action {
+            GameAction::MakeTerrain(position, _)
+                if Self::hill(&state.map_config) == Some(*position) =>
+            {
+                Err(RuleError::TileOccupied)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_victory(&self, state: &GameState) -> Option<(Player, GameOverReason)> {
+        let hill = Self::hill(&state.map_config)?;
+        (state.grid.get_tile(hill).owner() == Some(state.turn))
+            .then_some((state.turn, GameOverReason::HillHeld))
+    }
+}
+
+/// Every player starts with a farm next to their base, where there is room
+/// for one. Hold every farm on the board when your next turn comes round to
+/// win.
+pub struct CaptureAllFarms;
+
+impl GameMode for CaptureAllFarms {
+    fn setup(&self, state: &mut GameState) {
+        let Some(hp) = state
+            .rules
+            .levels
+            .get(PlayerTile::Farm, 1)
+            .map(|level| level.hp)
+        else {
+            return;
+        };
+
+        for player in state.map_config.players().collect::<Vec<_>>() {
+            let Some(base) = state.map_config.base(player) else {
+                continue;
+            };
+            let free = state.grid.topology.neighbours(base).find(|position| {
+                state.grid.in_bounds_index(position)
+                    && state.grid.get_tile(*position) == TileType::EMPTY
+            });
+            if let Some(position) = free {
+                let farm = TileType::EMPTY.with_owner(PlayerTile::Farm, player, 1, hp);
+                state.grid.set_tile(position, farm);
+            }
+        }
+    }
+
+    fn check_victory(&self, state: &GameState) -> Option<(Player, GameOverReason)> {
+        let owners = state
+            .grid
+            .get_tiles()
+            .filter(|tile| tile.player_tile() == Some(PlayerTile::Farm))
+            .map(TileType::owner)
+            .collect::<Vec<_>>();
+
+        (!owners.is_empty() && owners.iter().all(|&owner| owner == Some(state.turn)))
+            .then_some((state.turn, GameOverReason::FarmsHeld))
+    }
+}
+
+/// Whoever owns the most tiles once the turns of [`Mode::ScoreRace`] have
+/// been played wins. Only turns of the game phase count, see
+/// [`GameState::game_turn`]. Ties go to whoever is first in the turn order.
+pub struct ScoreRace;
+
+impl GameMode for ScoreRace {
+    fn check_victory(&self, state: &GameState) -> Option<(Player, GameOverReason)> {
+        let Mode::ScoreRace { turns } = state.mode else {
+            return None;
+        };
+        if state.game_turn <= turns {
+            return None;
+        }
+
+        // `max_by_key` keeps the last of equal scores
+        let winner = state
+            .players
+            .iter()
+            .rev()
+            .max_by_key(|&&player| state.score(player))?;
+        Some((*winner, GameOverReason::TurnLimit))
+    }
+}
+
+impl GameState {
+    /// Plays the match as `mode`, running its setup. Only meant for matches
+    /// nobody has moved in yet. A score race without a turn limit lasts
+    /// [`DEFAULT_TURN_LIMIT`] turns.
+    pub fn set_mode(&mut self, mut mode: Mode) {
+        if let Mode::ScoreRace { turns: turns @ 0 } = &mut mode {
+            *turns = DEFAULT_TURN_LIMIT;
+        }
+        self.mode = mode;
+        mode.game_mode().setup(self);
+    }
+
+    /// Tiles `player` owns, their base included.
+    pub fn score(&self, player: Player) -> usize {
+        self.grid
+            .get_tiles()
+            .filter(|tile| tile.owner() == Some(player))
+            .count()
+    }
+}
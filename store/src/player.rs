@@ -8,15 +8,10 @@ pub enum Player {
     Red,
     #[strum(serialize = "blue")]
     Blue,
-}
-
-impl Player {
-    pub fn other(&self) -> Player {
-        match self {
-            Player::Red => Player::Blue,
-            Player::Blue => Player::Red,
-        }
-    }
+    #[strum(serialize = "green")]
+    Green,
+    #[strum(serialize = "yellow")]
+    Yellow,
 }
 
 impl From<usize> for Player {
@@ -24,6 +19,8 @@ impl From<usize> for Player {
         match i {
             0 => Player::Red,
             1 => Player::Blue,
+            2 => Player::Green,
+            3 => Player::Yellow,
             _ => panic!("Invalid player index: {}", i),
         }
     }
@@ -0,0 +1,108 @@
+use std::io::{BufRead, Write};
+
+use anyhow::Context;
+
+use crate::*;
+
+/// One accepted input, or a move the server made on its own, and everything
+/// the server broadcast because of it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayEntry {
+    pub player: Player,
+    pub turn_number: usize,
+    /// Milliseconds since the unix epoch.
+    pub timestamp: u64,
+    pub event: TileEvent,
+    /// Set when there was no input to replay, e.g. for a
+    /// [`GameAction::Timeout`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<GameAction>,
+    pub changes: Vec<ClientEvent>,
+}
+
+/// The position a recording starts from, either a fresh board or a match
+/// resumed from a save.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayHeader {
+    pub state: GameState,
+}
+
+impl ReplayHeader {
+    pub fn new(state: &GameState) -> Self {
+        Self {
+            state: state.clone(),
+        }
+    }
+}
+
+/// A recorded match. On disk this is JSON lines: the [`ReplayHeader`] followed
+/// by one [`ReplayEntry`] per accepted action, so a crash only loses the line
+/// being written.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub header: ReplayHeader,
+    pub entries: Vec<ReplayEntry>,
+}
+
+impl Replay {
+    pub fn read(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut lines = reader.lines();
+
+        let header = lines.next().context("replay is empty")??;
+        let header = serde_json::from_str(&header).context("invalid replay header")?;
+
+        let entries = lines
+            .enumerate()
+            .map(|(i, line)| {
+                serde_json::from_str(&line?)
+                    .with_context(|| format!("invalid replay entry on line {}", i + 2))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self { header, entries })
+    }
+
+    /// The position the recorded match started from, with the recorded seats.
+    pub fn initial_state(&self) -> GameState {
+        self.header.state.clone()
+    }
+
+    /// Feeds every recorded event through the rules engine, yielding the
+    /// position after each one.
+    pub fn apply(&self, state: GameState) -> impl Iterator<Item = GameState> + '_ {
+        self.entries.iter().scan(state, |state, entry| {
+            let action = match &entry.action {
+                Some(action) => Some(action.clone()),
+                None => state.get_action(&entry.event).ok(),
+            };
+            if let Some(action) = action {
+                state.consume(&action);
+            }
+
+            Some(state.clone())
+        })
+    }
+}
+
+/// Appends a match to a replay file as it is played.
+pub struct ReplayWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> ReplayWriter<W> {
+    pub fn new(mut writer: W, state: &GameState) -> anyhow::Result<Self> {
+        serde_json::to_writer(&mut writer, &ReplayHeader::new(state))?;
+        writeln!(writer)?;
+        writer.flush()?;
+
+        Ok(Self { writer })
+    }
+
+    pub fn record(&mut self, entry: &ReplayEntry) -> anyhow::Result<()> {
+        serde_json::to_writer(&mut self.writer, entry)?;
+        writeln!(self.writer)?;
+        self.writer.flush()?;
+
+        Ok(())
+    }
+}
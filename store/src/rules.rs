@@ -0,0 +1,220 @@
+use std::{fs, path::Path};
+
+use anyhow::Context;
+
+use crate::*;
+
+/// The balance of a match: what things cost, how sturdy tiles are, how much
+/// players earn and how far towers reach. Chosen by the server when the match
+/// is created and announced to clients in [`ClientEvent::Init`].
+///
+/// Rule files are this struct as TOML or JSON, anything left out keeps its
+/// default, e.g.
+///
+/// ```toml
+/// name = "Skirmish"
+/// starting_resources = 10
+///
+/// [costs]
+/// make_tower = 3
+/// ```
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuleSet {
+    /// Shown to players, so they know which rules they are playing by.
+    pub name: String,
+    pub costs: CostTable,
+    pub levels: LevelTable,
+    /// HP a tile gets when captured, by the terrain under it.
+    pub terrain_hp: TerrainHp,
+    pub base_hp: usize,
+    /// Turns a damaged base takes to regain one HP.
+    pub base_regen_turns: usize,
+    pub neutral_tile_hp: usize,
+    /// Mountains each player may place during terrain placement, unless the
+    /// map sets its own.
+    pub mountain_budget: usize,
+    /// Water tiles each player may place during terrain placement, unless the
+    /// map sets its own.
+    pub water_budget: usize,
+    pub starting_resources: usize,
+    /// What the base pays into the stockpile every turn.
+    pub base_income: usize,
+    /// What every farm level pays into the stockpile every turn.
+    pub farm_income: usize,
+    pub action_points: usize,
+    /// Farm levels it takes to earn an extra action point per turn.
+    pub farms_per_action_point: usize,
+    /// Reach of a level 1 tower, every level above adds one.
+    pub tower_range: usize,
+    pub tower_damage: usize,
+}
+
+/// HP of a freshly captured tile on each terrain. Whatever a terrain gives
+/// over `none` stays with the tile as bonus HP through its upgrades.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TerrainHp {
+    pub none: usize,
+    pub water: usize,
+    pub mountain: usize,
+}
+
+impl Default for TerrainHp {
+    fn default() -> Self {
+        Self {
+            none: 1,
+            water: 0,
+            mountain: 2,
+        }
+    }
+}
+
+impl TerrainHp {
+    pub fn get(&self, terrain: Terrain) -> usize {
+        match terrain {
+            Terrain::None => self.none,
+            Terrain::Water => self.water,
+            Terrain::Mountain => self.mountain,
+        }
+    }
+}
+
+/// Why a rule file was refused by [`RuleSet::validate`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RuleSetError {
+    NoLevels(PlayerTile),
+    NoAttack { level: usize },
+    NoBaseHp,
+    NoActionPoints,
+    NoFarmsPerActionPoint,
+}
+
+impl std::fmt::Display for RuleSetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleSetError::NoLevels(player_tile) => {
+                write!(f, "{:?} needs at least one level", player_tile)
+            }
+            RuleSetError::NoAttack { level } => {
+                write!(f, "Tiles at level {} have no attack", level)
+            }
+            RuleSetError::NoBaseHp => write!(f, "Bases need at least one HP"),
+            RuleSetError::NoActionPoints => {
+                write!(f, "Players need at least one action point per turn")
+            }
+            RuleSetError::NoFarmsPerActionPoint => {
+                write!(f, "Extra action points need at least one farm level each")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RuleSetError {}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self {
+            name: "Default".to_string(),
+            costs: CostTable::default(),
+            levels: LevelTable::default(),
+            terrain_hp: TerrainHp::default(),
+            base_hp: 2,
+            base_regen_turns: 3,
+            neutral_tile_hp: 2,
+            mountain_budget: 5,
+            water_budget: 4,
+            starting_resources: 5,
+            base_income: 1,
+            farm_income: 1,
+            action_points: 2,
+            farms_per_action_point: 3,
+            tower_range: 1,
+            tower_damage: 1,
+        }
+    }
+}
+
+impl RuleSet {
+    /// Reads and validates a rule file, `.toml` files as TOML and anything
+    /// else as JSON.
+    pub fn load_from_path(path: &Path) -> anyhow::Result<Self> {
+        let text =
+            fs::read_to_string(path).with_context(|| format!("can't open {}", path.display()))?;
+        let rules: Self = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(&text).context("invalid rule file")?,
+            _ => serde_json::from_str(&text).context("invalid rule file")?,
+        };
+        rules.validate()?;
+
+        Ok(rules)
+    }
+
+    /// Checks that every kind of tile can be built, that tiles can attack at
+    /// every level and that every turn has action points to spend, the rules
+    /// assume all three.
+    pub fn validate(&self) -> Result<(), RuleSetError> {
+        for player_tile in [PlayerTile::Tile, PlayerTile::Farm, PlayerTile::Tower] {
+            if self.levels.levels(player_tile).is_empty() {
+                return Err(RuleSetError::NoLevels(player_tile));
+            }
+        }
+        let unarmed =
+            (1..=self.levels.tile.len()).find(|&level| self.levels.attack(level).is_none());
+        if let Some(level) = unarmed {
+            return Err(RuleSetError::NoAttack { level });
+        }
+        if self.base_hp == 0 {
+            return Err(RuleSetError::NoBaseHp);
+        }
+        if self.action_points == 0 {
+            return Err(RuleSetError::NoActionPoints);
+        }
+        if self.farms_per_action_point == 0 {
+            return Err(RuleSetError::NoFarmsPerActionPoint);
+        }
+
+        Ok(())
+    }
+
+    /// The rules with the map's own terrain budgets, if it has any.
+    pub fn for_map(mut self, config: &MapConfig) -> Self {
+        self.mountain_budget = config.mountain_budget.unwrap_or(self.mountain_budget);
+        self.water_budget = config.water_budget.unwrap_or(self.water_budget);
+        self
+    }
+
+    /// How far a tower at `level` reaches.
+    pub fn tower_reach(&self, level: usize) -> usize {
+        self.tower_range + level.saturating_sub(1)
+    }
+
+    /// The HP `tile` has while undamaged: its level's plus whatever its
+    /// terrain adds to tiles, or the rules' fixed HP for bases and neutral
+    /// tiles.
+    pub fn max_hp(&self, tile: TileType) -> Option<usize> {
+        match tile {
+            TileType::Neutral { .. } => Some(self.neutral_tile_hp),
+            TileType::Occupied {
+                player_tile: PlayerTile::Base,
+                ..
+            } => Some(self.base_hp),
+            TileType::Occupied {
+                player_tile: PlayerTile::Tile,
+                terrain,
+                level,
+                ..
+            } => {
+                let bonus = self
+                    .terrain_hp
+                    .get(terrain)
+                    .saturating_sub(self.terrain_hp.none);
+                Some(self.levels.get(PlayerTile::Tile, level)?.hp + bonus)
+            }
+            TileType::Occupied {
+                player_tile, level, ..
+            } => Some(self.levels.get(player_tile, level)?.hp),
+            TileType::Empty(_) => None,
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use anyhow::{bail, Context};
+
+use crate::*;
+
+/// Bumped once per release that changes the layout of [`GameState`] in a
+/// way the previous release's saves can't be read with. Changes in between
+/// releases share the number of the release they ship in.
+pub const SAVE_VERSION: u32 = 1;
+
+/// How a save is encoded on disk. JSON is readable and diffable, bincode is
+/// what the server autosaves with when asked for a compact file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveFormat {
+    Json,
+    Bincode,
+}
+
+impl SaveFormat {
+    /// `.json` files are JSON, anything else is bincode.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => SaveFormat::Json,
+            _ => SaveFormat::Bincode,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SaveFile<'a> {
+    version: u32,
+    state: &'a GameState,
+}
+
+impl GameState {
+    /// Writes the whole match, prefixed with [`SAVE_VERSION`].
+    pub fn save(&self, mut writer: impl Write, format: SaveFormat) -> anyhow::Result<()> {
+        match format {
+            SaveFormat::Json => serde_json::to_writer(
+                &mut writer,
+                &SaveFile {
+                    version: SAVE_VERSION,
+                    state: self,
+                },
+            )?,
+            SaveFormat::Bincode => {
+                bincode::serialize_into(&mut writer, &SAVE_VERSION)?;
+                bincode::serialize_into(&mut writer, self)?;
+            }
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Reads a match written by [`GameState::save`], refusing saves from
+    /// another version.
+    pub fn load(mut reader: impl Read, format: SaveFormat) -> anyhow::Result<Self> {
+        match format {
+            SaveFormat::Json => {
+                let mut save: serde_json::Value = serde_json::from_reader(reader)?;
+                check_version(save["version"].as_u64().context("save has no version")?)?;
+                Ok(serde_json::from_value(save["state"].take()).context("invalid save")?)
+            }
+            SaveFormat::Bincode => {
+                let version: u32 =
+                    bincode::deserialize_from(&mut reader).context("save has no version")?;
+                check_version(version.into())?;
+                Ok(bincode::deserialize_from(reader).context("invalid save")?)
+            }
+        }
+    }
+
+    /// Saves next to `path` first and then moves the file into place, so a
+    /// crash mid-write leaves the previous save intact.
+    pub fn save_to_path(&self, path: &Path) -> anyhow::Result<()> {
+        let tmp = path.with_extension("tmp");
+        self.save(
+            BufWriter::new(File::create(&tmp)?),
+            SaveFormat::from_path(path),
+        )?;
+        fs::rename(&tmp, path)?;
+
+        Ok(())
+    }
+
+    pub fn load_from_path(path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(path).with_context(|| format!("can't open {}", path.display()))?;
+        Self::load(BufReader::new(file), SaveFormat::from_path(path))
+    }
+}
+
+fn check_version(version: u64) -> anyhow::Result<()> {
+    if version != SAVE_VERSION as u64 {
+        bail!(
+            "save is version {} but only version {} is supported",
+            version,
+            SAVE_VERSION
+        );
+    }
+
+    Ok(())
+}
@@ -1,7 +1,5 @@
-use std::{sync::OnceLock, vec};
-
 use crate::*;
-use bevy::utils::HashMap;
+use bevy::utils::{HashMap, HashSet};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum GameInput {
@@ -9,31 +7,74 @@ pub enum GameInput {
     Keyboard(KeyCode),
 }
 
+impl GameInput {
+    /// Every input the client forwards to the server, the rest are its own.
+    pub const ALL: [GameInput; 8] = [
+        GameInput::Mouse(MouseButton::Left),
+        GameInput::Mouse(MouseButton::Right),
+        GameInput::Keyboard(KeyCode::Space),
+        GameInput::Keyboard(KeyCode::M),
+        GameInput::Keyboard(KeyCode::W),
+        GameInput::Keyboard(KeyCode::Return),
+        GameInput::Keyboard(KeyCode::T),
+        GameInput::Keyboard(KeyCode::R),
+    ];
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum GameAction {
-    Attack(Vec<Vec2>),
-    MakeFarm(Vec2),
-    Upgrade(Vec2),
-    Select(Vec2),
-    MakeTerrain(Vec2, Terrain),
+    Attack(Vec<GridPos>),
+    MakeFarm(GridPos),
+    MakeTower(GridPos),
+    Upgrade(GridPos),
+    /// Restores a damaged tile or base to full HP.
+    Repair(GridPos),
+    Select(GridPos),
+    MakeTerrain(GridPos, Terrain),
     SetTerrainMode(Terrain),
     EndTerrainPlacement,
     Deselect,
+    /// Hands the turn over with action points to spare.
+    EndTurn,
+    /// Played by the server when the player on turn runs out of time, see
+    /// [`GameState::tick`].
+    Timeout,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameState {
     pub id_to_player: HashMap<u64, Player>,
+    /// Players still in the game, in turn order.
+    pub players: Vec<Player>,
     pub turn: Player,
+    /// How many times the turn has been handed over so far.
+    pub turn_number: usize,
+    /// Which turn of the game phase is being played, counting from 1, or 0
+    /// during terrain placement.
+    pub game_turn: usize,
+    /// What the player on turn has left to spend this turn, see
+    /// [`GameAction::action_points`].
+    pub action_points: usize,
+    pub map_config: MapConfig,
     pub grid: TileGrid,
     pub attack_controller: AttackController,
     pub terrain_controller: TerrainCounter,
-    pub farm_counter: FarmCounter,
+    pub economy: Economy,
+    pub rules: RuleSet,
+    pub mode: Mode,
+    /// Turns each player's base has spent damaged since it last regained an
+    /// HP, indexed by `Player as usize`. See [`RuleSet::base_regen_turns`].
+    pub base_regen: Vec<usize>,
     pub game_phase: GamePhase,
+    pub clock: Clock,
+    /// Hides the board outside each player's vision, see
+    /// [`GameState::filter_events`].
+    pub fog: Option<FogOfWar>,
 }
 
 #[derive(Debug, Default, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub struct TileChange {
-    pub position: Vec2,
+    pub position: GridPos,
     pub tile: TileType,
 }
 
@@ -42,13 +83,175 @@ pub enum GamePhase {
     #[default]
     TerrainPlacement,
     Game,
+    Finished {
+        winner: Player,
+        reason: GameOverReason,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum GameOverReason {
+    BaseDestroyed,
+    NoTilesLeft,
+    Timeout,
+    /// See [`KingOfTheHill`].
+    HillHeld,
+    /// See [`ScoreRace`].
+    TurnLimit,
+    /// See [`CaptureAllFarms`].
+    FarmsHeld,
+}
+
+impl std::fmt::Display for GameOverReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameOverReason::BaseDestroyed => write!(f, "base destroyed"),
+            GameOverReason::NoTilesLeft => write!(f, "no tiles left"),
+            GameOverReason::Timeout => write!(f, "out of time"),
+            GameOverReason::HillHeld => write!(f, "held the hill"),
+            GameOverReason::TurnLimit => write!(f, "most tiles at the turn limit"),
+            GameOverReason::FarmsHeld => write!(f, "held every farm"),
+        }
+    }
+}
+
+impl GameAction {
+    /// Action points this costs. Moves on the board cost one, everything
+    /// else is free.
+    pub fn action_points(&self) -> usize {
+        match self {
+            GameAction::Attack(_)
+            | GameAction::MakeFarm(_)
+            | GameAction::MakeTower(_)
+            | GameAction::Upgrade(_)
+            | GameAction::Repair(_) => 1,
+            GameAction::Select(_)
+            | GameAction::MakeTerrain(..)
+            | GameAction::SetTerrainMode(_)
+            | GameAction::EndTerrainPlacement
+            | GameAction::Deselect
+            | GameAction::EndTurn
+            | GameAction::Timeout => 0,
+        }
+    }
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        Self::new(MapConfig::default())
+    }
 }
 
 impl GameState {
+    pub fn new(map_config: MapConfig) -> Self {
+        Self::with_rules(map_config, RuleSet::default())
+    }
+
+    /// A match on `map_config` played by `rules`, with the map's terrain
+    /// budgets taking precedence over the rules' own.
+    pub fn with_rules(map_config: MapConfig, rules: RuleSet) -> Self {
+        let players = map_config.player_count();
+        let rules = rules.for_map(&map_config);
+
+        Self {
+            id_to_player: HashMap::default(),
+            players: map_config.players().collect(),
+            turn: Player::default(),
+            turn_number: 0,
+            game_turn: 0,
+            action_points: 0,
+            grid: TileGrid::new(&map_config, &rules),
+            map_config,
+            attack_controller: AttackController::default(),
+            terrain_controller: TerrainCounter::new(players),
+            economy: Economy::new(players, &rules),
+            rules,
+            mode: Mode::default(),
+            base_regen: vec![0; players],
+            game_phase: GamePhase::default(),
+            clock: Clock::default(),
+            fog: None,
+        }
+    }
+
+    /// Everything a client needs to draw this position from scratch, sent
+    /// once every seat is filled.
+    pub fn sync_events(&self) -> Vec<ClientEvent> {
+        let mut events = vec![ClientEvent::Init(
+            self.map_config.clone(),
+            Box::new(self.grid.clone()),
+            Box::new(self.rules.clone()),
+        )];
+
+        match self.game_phase {
+            GamePhase::TerrainPlacement => events.extend([
+                ClientEvent::TerrainMode(self.terrain_controller.placement_mode),
+                self.resources_event(),
+            ]),
+            GamePhase::Game => events.extend([
+                ClientEvent::GamePhase(ClientState::Game),
+                self.resources_event(),
+                ClientEvent::ActionPoints(self.action_points),
+            ]),
+            GamePhase::Finished { winner, reason } => {
+                events.push(ClientEvent::GameOver { winner, reason });
+                return events;
+            }
+        }
+        events.push(ClientEvent::Turn(self.turn));
+        events.extend(self.clock_event());
+
+        if let Some(position) = self.attack_controller.selected {
+            events.push(ClientEvent::Select(position));
+        }
+
+        events
+    }
+
+    /// Starts the match straight in the game phase, for maps that already
+    /// come with their terrain.
+    pub fn skip_terrain_placement(&mut self) {
+        if self.game_phase == GamePhase::TerrainPlacement {
+            self.game_phase = GamePhase::Game;
+            self.start_turn();
+        }
+    }
+
+    /// Action points `player` gets per turn: the rules' base amount and one
+    /// more for every [`RuleSet::farms_per_action_point`] farm levels.
+    pub fn action_points_per_turn(&self, player: Player) -> usize {
+        let farm_levels = Economy::farm_levels(&self.grid, player);
+
+        self.rules.action_points
+            + farm_levels
+                .checked_div(self.rules.farms_per_action_point)
+                .unwrap_or(0)
+    }
+
+    /// Every seat's stockpile and income, for the HUD.
+    pub fn resources_event(&self) -> ClientEvent {
+        ClientEvent::Resources {
+            balance: self.economy.balance.iter().copied().map(Some).collect(),
+            income: self
+                .map_config
+                .players()
+                .map(|p| Some(Economy::income(&self.rules, &self.grid, p)))
+                .collect(),
+        }
+    }
+
     pub fn set_player_id(&mut self, player_id: u64, player: Player) {
         self.id_to_player.insert(player_id, player);
     }
 
+    /// The client sitting in `player`'s seat.
+    pub fn client_id(&self, player: Player) -> Option<u64> {
+        self.id_to_player
+            .iter()
+            .find(|(_, &p)| p == player)
+            .map(|(&id, _)| id)
+    }
+
     pub fn is_player(&self, client_id: u64) -> bool {
         if let Some(p) = self.id_to_player.get(&client_id) {
             return *p == self.turn;
@@ -56,173 +259,551 @@ impl GameState {
         false
     }
 
-    pub fn get_action(&self, tile_event: &TileEvent) -> Option<GameAction> {
+    /// The next player still in the game after the current one.
+    pub fn next_player(&self) -> Player {
+        let seats = self.map_config.player_count();
+
+        (1..=seats)
+            .map(|i| Player::from((self.turn as usize + i) % seats))
+            .find(|p| self.players.contains(p))
+            .unwrap_or(self.turn)
+    }
+
+    /// Hands the turn to the next player still in the game.
+    fn advance_turn(&mut self) -> Player {
+        let previous = self.turn;
+        self.turn = self.next_player();
+        self.turn_number += 1;
+        self.clock.switch(previous, self.turn);
+        if self.game_phase == GamePhase::Game {
+            self.start_turn();
+        }
+        self.turn
+    }
+
+    /// The player on turn collects their income and action points.
+    fn start_turn(&mut self) {
+        self.game_turn += 1;
+        self.economy.collect(&self.rules, &self.grid, self.turn);
+        self.action_points = self.action_points_per_turn(self.turn);
+    }
+
+    /// Ends the turn once the player on turn is out of action points,
+    /// otherwise tells everyone what they have left.
+    fn after_action(&mut self) -> Vec<ClientEvent> {
+        if self.action_points == 0 {
+            return self.end_turn();
+        }
+
+        vec![
+            self.resources_event(),
+            ClientEvent::ActionPoints(self.action_points),
+        ]
+    }
+
+    /// Lets the towers fire on the player who just moved and their base
+    /// recover, then hands the turn over.
+    fn end_turn(&mut self) -> Vec<ClientEvent> {
+        let mut events = Vec::new();
+
+        let mut changes = self.fire_towers();
+        changes.extend(self.regenerate_base());
+        if !changes.is_empty() {
+            events.push(ClientEvent::TileChanges(changes));
+        }
+
+        events.extend([
+            ClientEvent::Turn(self.advance_turn()),
+            self.resources_event(),
+            ClientEvent::ActionPoints(self.action_points),
+        ]);
+        events
+    }
+
+    /// Hands terrain placement over to the next player, or starts the game
+    /// once nobody still in it is seated after the player on turn. Placement
+    /// goes round the seats in order, so that's everyone having had a go.
+    fn end_placement_turn(&mut self) -> Vec<ClientEvent> {
+        let turn = self.turn as usize;
+        if self.players.iter().all(|&p| p as usize <= turn) {
+            self.game_phase = GamePhase::Game;
+            return vec![
+                ClientEvent::GamePhase(ClientState::Game),
+                ClientEvent::Turn(self.advance_turn()),
+                self.resources_event(),
+                ClientEvent::ActionPoints(self.action_points),
+            ];
+        }
+        vec![ClientEvent::Turn(self.advance_turn())]
+    }
+
+    /// Gives the base of the player on turn back an HP once it has spent
+    /// [`RuleSet::base_regen_turns`] of their turns damaged.
+    fn regenerate_base(&mut self) -> Option<TileChange> {
+        let player = self.turn;
+        let position = self.map_config.base(player)?;
+        let damaged = self.grid.get_tile(position).is_base(player) && self.missing_hp(position) > 0;
+        let regen = self.base_regen.get_mut(player as usize)?;
+
+        if !damaged {
+            *regen = 0;
+            return None;
+        }
+
+        *regen += 1;
+        if *regen < self.rules.base_regen_turns {
+            return None;
+        }
+
+        *regen = 0;
+        self.grid.get_tile_mut(position).heal(1);
+        Some(TileChange {
+            position,
+            tile: self.grid.get_tile(position),
+        })
+    }
+
+    /// Every tower not owned by the player on turn hits each of their tiles
+    /// in range for [`RuleSet::tower_damage`]. Destroyed tiles are cleared rather than
+    /// captured, towers don't take ground.
+    pub fn fire_towers(&mut self) -> Vec<TileChange> {
+        let target = self.turn;
+        let towers = self
+            .grid
+            .get_tiles()
+            .enumerate()
+            .filter_map(|(i, tile)| match *tile {
+                TileType::Occupied {
+                    player_tile: PlayerTile::Tower,
+                    owner,
+                    level,
+                    ..
+                } if owner != target => Some((self.grid.get_pos_from_index(i), level)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let damage = self.rules.tower_damage;
+        let mut hit = Vec::new();
+        for (tower, level) in towers {
+            let reach = self.rules.tower_reach(level);
+            for position in self.grid.tower_targets(tower, reach, target) {
+                if self.grid.get_tile_mut(position).damage(damage) == 0 {
+                    self.grid.get_tile_mut(position).empty();
+                }
+                if !hit.contains(&position) {
+                    hit.push(position);
+                }
+            }
+        }
+
+        hit.into_iter()
+            .map(|position| TileChange {
+                position,
+                tile: self.grid.get_tile(position),
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .chain(self.grid.update())
+            .collect()
+    }
+
+    /// Drops every player that has lost their base or all of their tiles from
+    /// the turn order, returning who was knocked out and why.
+    fn eliminate_defeated(&mut self) -> Vec<(Player, GameOverReason)> {
+        let defeated = self
+            .players
+            .iter()
+            .filter_map(|&p| self.grid.check_defeat(p).map(|reason| (p, reason)))
+            .collect::<Vec<_>>();
+
+        self.players
+            .retain(|p| !defeated.iter().any(|(defeated, _)| defeated == p));
+
+        defeated
+    }
+
+    /// Knocks `player` out of the match and clears their tiles off the board.
+    fn forfeit(&mut self, player: Player, reason: GameOverReason) -> Vec<ClientEvent> {
+        let mut changes = Vec::new();
+        for i in 0..self.grid.width * self.grid.height {
+            if self.grid.grid[i].owner() == Some(player) {
+                self.grid.grid[i].empty();
+                changes.push(TileChange {
+                    position: self.grid.get_pos_from_index(i),
+                    tile: self.grid.grid[i],
+                });
+            }
+        }
+
+        self.players.retain(|&p| p != player);
+        self.attack_controller.deselect();
+
+        if let &[winner] = &self.players[..] {
+            self.game_phase = GamePhase::Finished { winner, reason };
+            return vec![
+                ClientEvent::TileChanges(changes),
+                ClientEvent::Deselect,
+                ClientEvent::GameOver { winner, reason },
+            ];
+        }
+
+        let mut events = vec![
+            ClientEvent::TileChanges(changes),
+            ClientEvent::Eliminated { player, reason },
+            ClientEvent::Deselect,
+        ];
+        if self.turn == player && self.game_phase == GamePhase::TerrainPlacement {
+            events.extend(self.end_placement_turn());
+        } else if self.turn == player {
+            events.extend([
+                ClientEvent::Turn(self.advance_turn()),
+                ClientEvent::ActionPoints(self.action_points),
+            ]);
+        }
+        events
+    }
+
+    pub fn winner(&self) -> Option<Player> {
+        match self.game_phase {
+            GamePhase::Finished { winner, .. } => Some(winner),
+            _ => None,
+        }
+    }
+
+    pub fn get_action(&self, tile_event: &TileEvent) -> Result<GameAction, RuleError> {
+        let player = tile_event
+            .client_id()
+            .and_then(|client_id| self.id_to_player.get(&client_id).copied());
+        self.action_by(player, tile_event)
+    }
+
+    /// [`GameState::get_action`] for `tile_event` sent from `player`'s seat,
+    /// whichever client sits in it.
+    fn action_by(
+        &self,
+        player: Option<Player>,
+        tile_event: &TileEvent,
+    ) -> Result<GameAction, RuleError> {
+        if self.winner().is_some() {
+            return Err(RuleError::GameOver);
+        }
+
+        let on_turn = player == Some(self.turn);
+        let action = self.resolve(on_turn, tile_event)?;
+        self.mode.game_mode().validate(self, &action)?;
+        let needed = self.cost(&action);
+        let available = self.economy.balance(self.turn);
+        if needed > available {
+            return Err(RuleError::NotEnoughResources { needed, available });
+        }
+
+        Ok(action)
+    }
+
+    /// What `action` costs the player on turn. Upgrades are priced by the
+    /// level they reach, so this has to be asked before the action is
+    /// consumed.
+    pub fn cost(&self, action: &GameAction) -> usize {
+        match action {
+            GameAction::Upgrade(position) => self
+                .rules
+                .levels
+                .upgrade_cost(self.grid.get_tile(*position))
+                .unwrap_or(0),
+            GameAction::Repair(position) => self.rules.costs.repair * self.missing_hp(*position),
+            action => self.rules.costs.cost(action),
+        }
+    }
+
+    /// HP the tile at `position` is missing.
+    pub fn missing_hp(&self, position: GridPos) -> usize {
+        let tile = self.grid.get_tile(position);
+        self.rules
+            .max_hp(tile)
+            .zip(tile.hp())
+            .map_or(0, |(max, hp)| max.saturating_sub(hp))
+    }
+
+    /// The action `tile_event` asks for, whether or not it can be paid for.
+    /// `on_turn` is whether it was sent from the seat on turn.
+    fn resolve(&self, on_turn: bool, tile_event: &TileEvent) -> Result<GameAction, RuleError> {
         match &tile_event {
             TileEvent::TileAction {
-                client_id,
-                position,
-                action,
+                position, action, ..
             } if self.game_phase == GamePhase::Game => {
-                if !self.is_player(*client_id) || !TileGrid::in_bounds_index(position) {
-                    println!("invalid action");
-                    return None;
+                if !on_turn {
+                    return Err(RuleError::NotYourTurn);
+                }
+                if !self.grid.in_bounds_index(position) {
+                    return Err(RuleError::OutOfBounds);
                 }
 
                 match (
                     self.grid.get_tile(*position).player_tile(),
                     self.grid.get_tile(*position).owner(),
                     action,
-                    self.farm_counter.available_farms()[self.turn as usize],
                 ) {
-                    (Some(PlayerTile::Tile), Some(p), GameInput::Mouse(MouseButton::Right), _)
+                    (Some(PlayerTile::Tile), Some(p), GameInput::Mouse(MouseButton::Right))
                         if p == self.turn =>
                     {
-                        Some(GameAction::MakeFarm(*position))
+                        Ok(GameAction::MakeFarm(*position))
                     }
-                    (Some(PlayerTile::Tile), Some(p), GameInput::Mouse(MouseButton::Left), 1..)
-                    | (Some(PlayerTile::Farm), Some(p), GameInput::Mouse(MouseButton::Left), _)
+                    (Some(PlayerTile::Tile), Some(p), GameInput::Keyboard(KeyCode::T))
                         if p == self.turn =>
                     {
-                        Some(GameAction::Upgrade(*position))
+                        Ok(GameAction::MakeTower(*position))
                     }
-                    (.., GameInput::Mouse(MouseButton::Left), available @ 1..)
-                        if self
-                            .get_targets(tile_event)
-                            .map(|t| t.len() <= available)
-                            .unwrap_or(false) =>
-                    {
-                        Some(GameAction::Attack(
-                            self.get_targets(tile_event).unwrap_or_default(),
-                        ))
+                    (
+                        Some(
+                            player_tile @ (PlayerTile::Tile | PlayerTile::Farm | PlayerTile::Tower),
+                        ),
+                        Some(p),
+                        GameInput::Mouse(MouseButton::Left),
+                    ) if p == self.turn => {
+                        let level = self.grid.get_tile(*position).level().unwrap_or(1);
+                        if level >= self.rules.levels.max_level(player_tile) {
+                            Err(RuleError::MaxLevel)
+                        } else {
+                            Ok(GameAction::Upgrade(*position))
+                        }
                     }
-                    (.., GameInput::Mouse(MouseButton::Left), 0) => {
-                        println!("no available attacks");
-                        None
-                    }
-                    (.., GameInput::Mouse(MouseButton::Left), _) => {
-                        println!("invalid action c1: {:?}", self.get_targets(tile_event));
-                        None
-                    }
-                    _ => {
-                        println!("invalid action c2");
-                        None
+                    (Some(_), Some(p), GameInput::Keyboard(KeyCode::R)) if p == self.turn => {
+                        if self.missing_hp(*position) == 0 {
+                            Err(RuleError::NotDamaged)
+                        } else {
+                            Ok(GameAction::Repair(*position))
+                        }
                     }
+                    (.., GameInput::Mouse(MouseButton::Left)) => match self.get_targets(tile_event)
+                    {
+                        Some(targets) => Ok(GameAction::Attack(targets)),
+                        None => Err(RuleError::TargetNotAdjacent),
+                    },
+                    _ => Err(RuleError::InvalidInput),
                 }
             }
 
-            TileEvent::ToggleSelect {
-                client_id,
-                position,
-            } if self.game_phase == GamePhase::Game => {
-                if self.is_player(*client_id) && self.attack_controller.selected.is_some() {
-                    Some(GameAction::Deselect)
-                } else if self.is_player(*client_id)
-                    && (self.grid.get_tile(*position).owner() == Some(self.turn)
-                        && self.grid.get_tile(*position).player_tile() == Some(PlayerTile::Tile))
+            TileEvent::ToggleSelect { position, .. } if self.game_phase == GamePhase::Game => {
+                if !on_turn {
+                    Err(RuleError::NotYourTurn)
+                } else if self.attack_controller.selected.is_some() {
+                    Ok(GameAction::Deselect)
+                } else if self.grid.get_tile(*position).owner() == Some(self.turn)
+                    && self.grid.get_tile(*position).player_tile() == Some(PlayerTile::Tile)
                 {
-                    Some(GameAction::Select(*position))
+                    Ok(GameAction::Select(*position))
                 } else {
-                    None
+                    Err(RuleError::NotSelectable)
+                }
+            }
+
+            TileEvent::EndTurn { .. } if self.game_phase == GamePhase::Game => {
+                if !on_turn {
+                    Err(RuleError::NotYourTurn)
+                } else {
+                    Ok(GameAction::EndTurn)
                 }
             }
 
             TileEvent::TerrainAction {
-                client_id,
-                position,
-                action,
+                position, action, ..
             } if self.game_phase == GamePhase::TerrainPlacement => {
-                if !self.is_player(*client_id) {
-                    println!("Not valid");
-                    return None;
+                if !on_turn {
+                    return Err(RuleError::NotYourTurn);
                 }
 
                 match (self.grid.get_tile(*position).owner(), action) {
-                    (None, GameInput::Mouse(MouseButton::Left))
-                        if TileGrid::in_bounds_index(position)
-                            && self.terrain_controller.can_add(self.turn) =>
-                    {
-                        Some(GameAction::MakeTerrain(
-                            *position,
-                            self.terrain_controller.placement_mode,
-                        ))
+                    (None, GameInput::Mouse(_)) if !self.grid.in_bounds_index(position) => {
+                        Err(RuleError::OutOfBounds)
                     }
-                    (None, GameInput::Mouse(MouseButton::Right))
-                        if TileGrid::in_bounds_index(position) =>
-                    {
-                        Some(GameAction::MakeTerrain(*position, Terrain::None))
+                    (None, GameInput::Mouse(_)) if self.map_config.is_preplaced(*position) => {
+                        Err(RuleError::TileOccupied)
+                    }
+                    (None, GameInput::Mouse(MouseButton::Left)) => {
+                        if self.terrain_controller.can_add(self.turn, &self.rules) {
+                            Ok(GameAction::MakeTerrain(
+                                *position,
+                                self.terrain_controller.placement_mode,
+                            ))
+                        } else {
+                            Err(RuleError::TerrainLimitReached(
+                                self.terrain_controller.placement_mode,
+                            ))
+                        }
                     }
+                    (None, GameInput::Mouse(MouseButton::Right)) => {
+                        Ok(GameAction::MakeTerrain(*position, Terrain::None))
+                    }
+                    (Some(_), GameInput::Mouse(_)) => Err(RuleError::TileOccupied),
                     (None, GameInput::Keyboard(KeyCode::M)) => {
-                        Some(GameAction::SetTerrainMode(Terrain::Mountain))
+                        Ok(GameAction::SetTerrainMode(Terrain::Mountain))
                     }
                     (None, GameInput::Keyboard(KeyCode::W)) => {
-                        Some(GameAction::SetTerrainMode(Terrain::Water))
+                        Ok(GameAction::SetTerrainMode(Terrain::Water))
                     }
                     (None, GameInput::Keyboard(KeyCode::Return)) => {
-                        Some(GameAction::EndTerrainPlacement)
+                        Ok(GameAction::EndTerrainPlacement)
                     }
-                    _ => None,
+                    _ => Err(RuleError::InvalidInput),
                 }
             }
-            _ => None,
+
+            TileEvent::TileAction { .. }
+            | TileEvent::ToggleSelect { .. }
+            | TileEvent::EndTurn { .. }
+            | TileEvent::TerrainAction { .. } => Err(RuleError::WrongPhase),
+            _ => Err(RuleError::InvalidInput),
         }
     }
 
-    /// Consume a game action into the game state.
+    /// Every input `player` could send that [`GameState::get_action`] accepts
+    /// right now, paired with the action it asks for: each of
+    /// [`GameInput::ALL`] on every tile of the board. Inputs that don't depend
+    /// on the tile, like ending the turn, are listed once. The events carry
+    /// the client id of `player`'s seat, or 0 if nobody sits there.
+    fn legal_inputs(&self, player: Player) -> Vec<(TileEvent, GameAction)> {
+        if self.turn != player {
+            return Vec::new();
+        }
+        let client_state = match self.game_phase {
+            GamePhase::TerrainPlacement => ClientState::Terrain,
+            GamePhase::Game => ClientState::Game,
+            GamePhase::Finished { .. } => return Vec::new(),
+        };
+        let client_id = self.client_id(player).unwrap_or_default();
+
+        let mut seen = HashSet::new();
+        (0..self.grid.width * self.grid.height)
+            .map(|i| self.grid.get_pos_from_index(i))
+            .flat_map(|position| {
+                GameInput::ALL
+                    .map(|input| TileEvent::from_input(client_id, position, input, &client_state))
+            })
+            .filter(|event| seen.insert(event.clone()))
+            .filter_map(|event| {
+                let action = self.action_by(Some(player), &event).ok()?;
+                Some((event, action))
+            })
+            .collect()
+    }
+
+    /// Every input `player` could send that [`GameState::get_action`] accepts
+    /// right now, see [`GameState::legal_inputs`].
+    pub fn legal_events(&self, player: Player) -> Vec<TileEvent> {
+        self.legal_inputs(player)
+            .into_iter()
+            .map(|(event, _)| event)
+            .collect()
+    }
+
+    /// Everything `player` may do right now, each action once, whether or not
+    /// anyone sits in their seat. Built from the same inputs as
+    /// [`GameState::legal_events`], so it never disagrees with
+    /// [`GameState::get_action`].
+    pub fn legal_actions(&self, player: Player) -> Vec<GameAction> {
+        let mut seen = HashSet::new();
+        self.legal_inputs(player)
+            .into_iter()
+            .map(|(_, action)| action)
+            .filter(|action| seen.insert(action.clone()))
+            .collect()
+    }
+
+    /// Consume a game action into the game state, then let the
+    /// [`GameMode`] add its effects and decide whether the handover of a turn
+    /// won the match.
     pub fn consume(&mut self, action: &GameAction) -> Vec<ClientEvent> {
+        let mode = self.mode.game_mode();
+        let turn_number = self.turn_number;
+
+        let mut events = self.apply(action);
+        events.extend(mode.after_action(self, action));
+
+        if self.turn_number != turn_number && self.game_phase == GamePhase::Game {
+            if let Some((winner, reason)) = mode.check_victory(self) {
+                self.game_phase = GamePhase::Finished { winner, reason };
+                self.attack_controller.deselect();
+                events.push(ClientEvent::GameOver { winner, reason });
+            }
+        }
+
+        events
+    }
+
+    /// Plays `action` by the core rules.
+    fn apply(&mut self, action: &GameAction) -> Vec<ClientEvent> {
+        let cost = self.cost(action);
+        self.economy.spend(self.turn, cost);
+        self.action_points = self.action_points.saturating_sub(action.action_points());
+
         match *action {
             GameAction::Attack(ref targets) => {
                 self.attack_controller.deselect();
-                vec![
-                    ClientEvent::TileChanges(
-                        targets
-                            .iter()
-                            .map(|&t| {
-                                if self.grid.get_tile_mut(t).damage(1) == 0
-                                    || self.grid.get_tile(t).player_tile() == Some(PlayerTile::Farm)
-                                {
-                                    self.grid.capture(t, self.turn);
-                                }
-                                TileChange {
-                                    position: t,
-                                    tile: self.grid.get_tile(t),
-                                }
-                            })
-                            .collect::<Vec<_>>()
-                            .into_iter()
-                            .chain(self.grid.update())
-                            .collect(),
-                    ),
-                    ClientEvent::Turn({
-                        self.turn = self.turn.other();
-                        self.turn
-                    }),
-                    ClientEvent::Farms({
-                        self.farm_counter.update(&self.grid);
-                        self.farm_counter.available_farms()
-                    }),
-                    ClientEvent::Deselect,
-                ]
+                let mut changes = targets
+                    .iter()
+                    .map(|&t| {
+                        if self.grid.get_tile_mut(t).damage(1) == 0
+                            || self.grid.get_tile(t).player_tile() == Some(PlayerTile::Farm)
+                        {
+                            self.grid.capture(t, self.turn, &self.rules);
+                        }
+                        TileChange {
+                            position: t,
+                            tile: self.grid.get_tile(t),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                // Before the tiles cut off from a lost base go, a base that
+                // was all its owner had left is told apart from one that wasn't
+                let defeated = self.eliminate_defeated();
+                changes.extend(self.grid.update());
+                if let (&[winner], Some(&(_, reason))) = (&self.players[..], defeated.last()) {
+                    self.game_phase = GamePhase::Finished { winner, reason };
+                    return vec![
+                        ClientEvent::TileChanges(changes),
+                        ClientEvent::Deselect,
+                        ClientEvent::GameOver { winner, reason },
+                    ];
+                }
+
+                let mut events = vec![ClientEvent::TileChanges(changes)];
+                events.extend(
+                    defeated
+                        .into_iter()
+                        .map(|(player, reason)| ClientEvent::Eliminated { player, reason }),
+                );
+                events.extend(self.after_action());
+                events.push(ClientEvent::Deselect);
+                events
             }
 
             GameAction::Upgrade(position) => {
-                self.grid.upgrade(position);
+                self.grid.upgrade(position, &self.rules.levels);
                 self.attack_controller.deselect();
 
-                vec![
-                    ClientEvent::TileChanges(vec![TileChange {
-                        position,
-                        tile: self.grid.get_tile(position),
-                    }]),
-                    ClientEvent::Turn({
-                        self.turn = self.turn.other();
-                        self.turn
-                    }),
-                    ClientEvent::Farms({
-                        self.farm_counter.update(&self.grid);
-                        self.farm_counter.available_farms()
-                    }),
-                    ClientEvent::Deselect,
-                ]
+                let mut events = vec![ClientEvent::TileChanges(vec![TileChange {
+                    position,
+                    tile: self.grid.get_tile(position),
+                }])];
+                events.extend(self.after_action());
+                events.push(ClientEvent::Deselect);
+                events
+            }
+
+            GameAction::Repair(position) => {
+                let missing = self.missing_hp(position);
+                self.grid.get_tile_mut(position).heal(missing);
+                self.attack_controller.deselect();
+
+                let mut events = vec![ClientEvent::TileChanges(vec![TileChange {
+                    position,
+                    tile: self.grid.get_tile(position),
+                }])];
+                events.extend(self.after_action());
+                events.push(ClientEvent::Deselect);
+                events
             }
 
             GameAction::MakeFarm(position) => {
@@ -233,26 +814,36 @@ impl GameState {
                         terrain: Terrain::None,
                         owner: self.turn,
                         level: 1,
-                        hp: 1,
+                        hp: self.rules.levels.farm[0].hp,
                     },
                 );
                 self.attack_controller.deselect();
 
-                vec![
-                    ClientEvent::TileChanges(vec![TileChange {
-                        position,
-                        tile: self.grid.get_tile(position),
-                    }]),
-                    ClientEvent::Turn({
-                        self.turn = self.turn.other();
-                        self.turn
-                    }),
-                    ClientEvent::Farms({
-                        self.farm_counter.update(&self.grid);
-                        self.farm_counter.available_farms()
-                    }),
-                    ClientEvent::Deselect,
-                ]
+                let mut events = vec![ClientEvent::TileChanges(vec![TileChange {
+                    position,
+                    tile: self.grid.get_tile(position),
+                }])];
+                events.extend(self.after_action());
+                events.push(ClientEvent::Deselect);
+                events
+            }
+
+            GameAction::MakeTower(position) => {
+                let tile = self.grid.get_tile(position);
+                let hp = self.rules.levels.tower[0].hp;
+                self.grid.set_tile(
+                    position,
+                    tile.with_owner(PlayerTile::Tower, self.turn, 1, hp),
+                );
+                self.attack_controller.deselect();
+
+                let mut events = vec![ClientEvent::TileChanges(vec![TileChange {
+                    position,
+                    tile: self.grid.get_tile(position),
+                }])];
+                events.extend(self.after_action());
+                events.push(ClientEvent::Deselect);
+                events
             }
 
             GameAction::Select(position) => {
@@ -269,10 +860,13 @@ impl GameState {
             GameAction::MakeTerrain(position, terrain) => {
                 self.grid.set_tile(position, TileType::Empty(terrain));
                 self.terrain_controller.set(terrain, self.turn);
-                vec![ClientEvent::TileChanges(vec![TileChange {
-                    position,
-                    tile: self.grid.get_tile(position),
-                }])]
+                vec![
+                    ClientEvent::TileChanges(vec![TileChange {
+                        position,
+                        tile: self.grid.get_tile(position),
+                    }]),
+                    self.resources_event(),
+                ]
             }
 
             GameAction::SetTerrainMode(terrain) => {
@@ -280,28 +874,26 @@ impl GameState {
                 vec![ClientEvent::TerrainMode(terrain)]
             }
 
-            GameAction::EndTerrainPlacement => {
-                if self.turn == Player::Blue {
-                    self.game_phase = GamePhase::Game;
-                    return vec![
-                        ClientEvent::GamePhase(ClientState::Game),
-                        ClientEvent::Turn({
-                            self.turn = self.turn.other();
-                            self.turn
-                        }),
-                        ClientEvent::Farms({
-                            self.farm_counter.update(&self.grid);
-                            self.farm_counter.available_farms()
-                        }),
-                    ];
+            GameAction::EndTerrainPlacement => self.end_placement_turn(),
+
+            GameAction::Timeout => match self.clock.control {
+                Some(TimeControl::Bank { .. }) => self.forfeit(self.turn, GameOverReason::Timeout),
+                _ if self.game_phase == GamePhase::TerrainPlacement => {
+                    self.apply(&GameAction::EndTerrainPlacement)
                 }
-                self.turn = self.turn.other();
-                vec![ClientEvent::Turn(self.turn)]
+                _ => self.apply(&GameAction::EndTurn),
+            },
+
+            GameAction::EndTurn => {
+                self.attack_controller.deselect();
+                let mut events = vec![ClientEvent::Deselect];
+                events.extend(self.end_turn());
+                events
             }
         }
     }
 
-    pub fn get_targets(&self, tile_event: &TileEvent) -> Option<Vec<Vec2>> {
+    pub fn get_targets(&self, tile_event: &TileEvent) -> Option<Vec<GridPos>> {
         let (position, action) = match tile_event {
             TileEvent::TileAction {
                 position, action, ..
@@ -320,73 +912,22 @@ impl GameState {
                 .selected_level
                 .or_else(|| self.grid.get_tile(origin).level())?;
 
-            if !attack_is_valid(origin, position, level) {
-                println!("invalid attack");
-                return None;
-            }
+            // Attacks go straight along a line of the board
+            let topology = self.grid.topology;
+            let direction = topology.direction(origin, position)?;
+            debug!("direction: {}, level: {}", direction, level);
 
-            let direction = (position - origin).normalize();
-            println!("direction: {}", direction);
-
-            return Some(match level {
-                2 => vec![origin + direction, origin + direction * 2.0],
-                3 => vec![
-                    origin + direction,
-                    origin + direction + Vec2::new(direction.y, -direction.x),
-                    origin + direction + Vec2::new(-direction.y, direction.x),
-                ],
-                _ => vec![position],
-            });
+            let targets = self
+                .rules
+                .levels
+                .attack(level)?
+                .iter()
+                .map(|&offset| topology.facing(origin, direction, offset))
+                .collect::<Vec<_>>();
+
+            return targets.contains(&position).then_some(targets);
         }
 
         None
     }
 }
-
-fn attack_vectors() -> &'static HashMap<usize, Vec<Vec2>> {
-    static HASHMAP: OnceLock<HashMap<usize, Vec<Vec2>>> = OnceLock::new();
-    HASHMAP.get_or_init(|| {
-        let mut m = HashMap::new();
-        m.insert(
-            1,
-            vec![
-                Vec2::new(1.0, 0.0),
-                Vec2::new(-1.0, 0.0),
-                Vec2::new(0.0, 1.0),
-                Vec2::new(0.0, -1.0),
-            ],
-        );
-        m.insert(
-            2,
-            vec![
-                Vec2::new(1.0, 0.0),
-                Vec2::new(-1.0, 0.0),
-                Vec2::new(0.0, 1.0),
-                Vec2::new(0.0, -1.0),
-                Vec2::new(2.0, 0.0),
-                Vec2::new(-2.0, 0.0),
-                Vec2::new(0.0, 2.0),
-                Vec2::new(0.0, -2.0),
-            ],
-        );
-        m.insert(
-            3,
-            vec![
-                Vec2::new(1.0, 0.0),
-                Vec2::new(-1.0, 0.0),
-                Vec2::new(0.0, 1.0),
-                Vec2::new(0.0, -1.0),
-            ],
-        );
-        m
-    })
-}
-
-fn attack_is_valid(origin: Vec2, target: Vec2, level: usize) -> bool {
-    let diff = target - origin;
-    info!("{}, level: {}", diff, level);
-
-    attack_vectors()
-        .get(&level)
-        .map_or(false, |v| v.contains(&diff))
-}
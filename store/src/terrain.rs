@@ -1,27 +1,33 @@
 use crate::*;
 
-#[derive(Resource, Clone, Debug)]
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
 pub struct TerrainCounter {
     pub placement_mode: Terrain,
-    pub mountain_count: [usize; 2],
-    pub water_count: [usize; 2],
+    pub mountain_count: Vec<usize>,
+    pub water_count: Vec<usize>,
 }
 
 impl Default for TerrainCounter {
     fn default() -> Self {
+        Self::new(2)
+    }
+}
+
+impl TerrainCounter {
+    pub fn new(players: usize) -> Self {
         Self {
             placement_mode: Terrain::Mountain,
-            mountain_count: [0, 0],
-            water_count: [0, 0],
+            mountain_count: vec![0; players],
+            water_count: vec![0; players],
         }
     }
-}
 
-impl TerrainCounter {
-    pub fn can_add(&self, player: Player) -> bool {
+    /// Whether `player` has any of the budget left for the current placement
+    /// mode.
+    pub fn can_add(&self, player: Player, rules: &RuleSet) -> bool {
         match self.placement_mode {
-            Terrain::Mountain => self.mountain_count[player as usize] < MAX_MOUNTAIN_COUNT,
-            Terrain::Water => self.water_count[player as usize] < MAX_WATER_COUNT,
+            Terrain::Mountain => self.mountain_count[player as usize] < rules.mountain_budget,
+            Terrain::Water => self.water_count[player as usize] < rules.water_budget,
             _ => true,
         }
     }
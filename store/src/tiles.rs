@@ -10,6 +10,10 @@ pub enum TileType {
         level: usize,
         hp: usize,
     },
+    /// Owned by nobody, placed by the map. Captured like any enemy tile.
+    Neutral {
+        hp: usize,
+    },
 }
 
 impl Default for TileType {
@@ -42,6 +46,16 @@ impl TileType {
         )
     }
 
+    pub fn is_tower(&self) -> bool {
+        matches!(
+            self,
+            TileType::Occupied {
+                player_tile: PlayerTile::Tower,
+                ..
+            }
+        )
+    }
+
     pub fn is_empty(&self) -> bool {
         matches!(self, TileType::Empty(Terrain::None))
     }
@@ -50,6 +64,7 @@ impl TileType {
         match self {
             TileType::Empty(terrain) => *terrain,
             TileType::Occupied { terrain, .. } => *terrain,
+            TileType::Neutral { .. } => Terrain::None,
         }
     }
 
@@ -75,6 +90,13 @@ impl TileType {
                 level,
                 hp,
             },
+            TileType::Neutral { .. } => TileType::Occupied {
+                player_tile,
+                terrain: Terrain::None,
+                owner: player,
+                level,
+                hp,
+            },
         }
     }
 
@@ -92,18 +114,27 @@ impl TileType {
         }
     }
 
+    pub fn hp(&self) -> Option<usize> {
+        match self {
+            TileType::Occupied { hp, .. } | TileType::Neutral { hp } => Some(*hp),
+            TileType::Empty(_) => None,
+        }
+    }
+
     pub fn is_base(&self, player: Player) -> bool {
         matches!(self, TileType::Occupied{player_tile: PlayerTile::Base, owner, ..} if *owner == player)
     }
 
     pub fn empty(&mut self) {
-        if let TileType::Occupied { terrain, .. } = self {
-            *self = TileType::Empty(*terrain);
+        match self {
+            TileType::Occupied { terrain, .. } => *self = TileType::Empty(*terrain),
+            TileType::Neutral { .. } => *self = TileType::EMPTY,
+            TileType::Empty(_) => (),
         }
     }
 
     pub fn damage(&mut self, damage: usize) -> usize {
-        if let TileType::Occupied { hp, .. } = self {
+        if let TileType::Occupied { hp, .. } | TileType::Neutral { hp } = self {
             *hp = hp.saturating_sub(damage);
             *hp
         } else {
@@ -111,17 +142,17 @@ impl TileType {
         }
     }
 
-    pub fn upgrade(&mut self) {
-        if let TileType::Occupied { player_tile, terrain, owner, level, hp } = self {
-            *level += 1;
-            *hp += 1;
-            *self = TileType::Occupied {
-                player_tile: *player_tile,
-                terrain: *terrain,
-                owner: *owner,
-                level: *level,
-                hp: *hp,
-            };
+    /// Gives back `amount` HP, capping is up to the caller.
+    pub fn heal(&mut self, amount: usize) {
+        if let TileType::Occupied { hp, .. } = self {
+            *hp += amount;
+        }
+    }
+
+    /// Goes up a level as described by `levels`, unless already at the top.
+    pub fn upgrade(&mut self, levels: &LevelTable) {
+        if let Some(upgraded) = levels.upgraded(*self) {
+            *self = upgraded;
         }
     }
 }
@@ -0,0 +1,202 @@
+use strum::{Display, EnumIter, EnumString};
+
+use crate::*;
+
+const SQUARE_DIRECTIONS: [GridPos; 4] =
+    [GridPos(1, 0), GridPos(-1, 0), GridPos(0, 1), GridPos(0, -1)];
+
+/// Axial steps in order round the cell, turning the same way as
+/// [`GridPos::rotate_ccw`].
+const HEX_DIRECTIONS: [GridPos; 6] = [
+    GridPos(1, 0),
+    GridPos(0, 1),
+    GridPos(-1, 1),
+    GridPos(-1, 0),
+    GridPos(0, -1),
+    GridPos(1, -1),
+];
+
+/// How the cells of a board touch. Boards are stored as `width` x `height`
+/// rows either way, hex boards shift every odd row half a cell to the right
+/// so each cell has six neighbours.
+///
+/// The rules step between cells through the topology rather than by adding
+/// [`GridPos`] offsets. Internally it works on lattice coordinates, where a
+/// step is the same vector everywhere: the board coordinates themselves on
+/// square boards, axial coordinates on hex ones.
+#[derive(
+    Resource,
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    EnumString,
+    Display,
+    EnumIter,
+    Serialize,
+    Deserialize,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum Topology {
+    #[default]
+    Square,
+    Hex,
+}
+
+impl Topology {
+    /// Unit steps to every neighbour in lattice coordinates.
+    pub fn directions(self) -> &'static [GridPos] {
+        match self {
+            Topology::Square => &SQUARE_DIRECTIONS,
+            Topology::Hex => &HEX_DIRECTIONS,
+        }
+    }
+
+    fn to_lattice(self, position: GridPos) -> GridPos {
+        match self {
+            Topology::Square => position,
+            Topology::Hex => GridPos(position.0 - position.1.div_euclid(2), position.1),
+        }
+    }
+
+    fn to_board(self, lattice: GridPos) -> GridPos {
+        match self {
+            Topology::Square => lattice,
+            Topology::Hex => GridPos(lattice.0 + lattice.1.div_euclid(2), lattice.1),
+        }
+    }
+
+    /// Every cell touching `position`, whether or not it is on the board.
+    pub fn neighbours(self, position: GridPos) -> impl Iterator<Item = GridPos> {
+        let lattice = self.to_lattice(position);
+        self.directions()
+            .iter()
+            .map(move |&dir| self.to_board(lattice + dir))
+    }
+
+    /// Fewest steps between `a` and `b` on an empty board.
+    pub fn distance(self, a: GridPos, b: GridPos) -> usize {
+        let GridPos(dq, dr) = self.to_lattice(a) - self.to_lattice(b);
+        match self {
+            Topology::Square => (dq.abs() + dr.abs()) as usize,
+            Topology::Hex => ((dq.abs() + dr.abs() + (dq + dr).abs()) / 2) as usize,
+        }
+    }
+
+    /// `center` and every cell at most `range` steps from it, whether or not
+    /// they are on the board.
+    pub fn within(self, center: GridPos, range: usize) -> impl Iterator<Item = GridPos> {
+        let range = range as i32;
+        (-range..=range)
+            .flat_map(move |dr| (-range..=range).map(move |dq| GridPos(dq, dr)))
+            .map(move |offset| self.to_board(self.to_lattice(center) + offset))
+            .filter(move |&position| self.distance(center, position) <= range as usize)
+    }
+
+    /// The step leading from `from` straight to `to`, or `None` if they
+    /// aren't on a common line. Lines run along rows and columns on square
+    /// boards and along the three axes on hex ones.
+    pub fn direction(self, from: GridPos, to: GridPos) -> Option<GridPos> {
+        let GridPos(dq, dr) = self.to_lattice(to) - self.to_lattice(from);
+        let straight = match self {
+            Topology::Square => dq == 0 || dr == 0,
+            Topology::Hex => dq == 0 || dr == 0 || dq == -dr,
+        };
+
+        (straight && from != to).then(|| GridPos(dq, dr).signum())
+    }
+
+    /// The cells from `from` onwards along `direction`, as returned by
+    /// [`Topology::direction`], without `from` itself.
+    pub fn line(self, from: GridPos, direction: GridPos) -> impl Iterator<Item = GridPos> {
+        let lattice = self.to_lattice(from);
+        (1..).map(move |steps| self.to_board(lattice + direction * steps))
+    }
+
+    /// `origin` moved by `offset`, an offset written for an attack towards
+    /// `(1, 0)` on a square board and turned to face `direction` instead.
+    /// The first component goes along `direction`, the second to its side:
+    /// a quarter turn away on square boards, a third of a turn on hex ones,
+    /// so `(1, 1)` and `(1, -1)` are the cells flanking the one in front.
+    pub fn facing(self, origin: GridPos, direction: GridPos, offset: GridPos) -> GridPos {
+        let GridPos(ahead, side) = offset;
+        let side_step = match self {
+            Topology::Square => direction.rotate_ccw(),
+            Topology::Hex => {
+                let index = HEX_DIRECTIONS
+                    .iter()
+                    .position(|&dir| dir == direction)
+                    .unwrap_or_default();
+                HEX_DIRECTIONS[(index + if side < 0 { 4 } else { 2 }) % HEX_DIRECTIONS.len()]
+                    * side.signum()
+            }
+        };
+
+        self.to_board(self.to_lattice(origin) + direction * ahead + side_step * side)
+    }
+
+    /// World space centre of `position` as the client draws it.
+    pub fn world_position(self, GridPos(x, y): GridPos) -> Position {
+        let shift = match self {
+            Topology::Hex if y.rem_euclid(2) == 1 => TILE_SIZE / 2.0,
+            _ => 0.0,
+        };
+
+        Position(Vec2::new(x as f32 * TILE_SIZE + shift, y as f32 * TILE_SIZE) + TILE_SIZE / 2.0)
+    }
+
+    /// Corners of a cell as the client draws it, counter-clockwise round its
+    /// centre. Hex cells are as wide as a square one and overlap the rows
+    /// above and below, so every point is in the cell with the closest
+    /// centre.
+    pub fn outline(self) -> Vec<Vec2> {
+        let half = TILE_SIZE / 2.0;
+        match self {
+            Topology::Square => vec![
+                Vec2::new(half, half),
+                Vec2::new(-half, half),
+                Vec2::new(-half, -half),
+                Vec2::new(half, -half),
+            ],
+            Topology::Hex => vec![
+                Vec2::new(0.0, TILE_SIZE * 5.0 / 8.0),
+                Vec2::new(-half, TILE_SIZE * 3.0 / 8.0),
+                Vec2::new(-half, -TILE_SIZE * 3.0 / 8.0),
+                Vec2::new(0.0, -TILE_SIZE * 5.0 / 8.0),
+                Vec2::new(half, -TILE_SIZE * 3.0 / 8.0),
+                Vec2::new(half, TILE_SIZE * 3.0 / 8.0),
+            ],
+        }
+    }
+
+    /// The cell drawn under the world space `point`, see
+    /// [`Topology::outline`].
+    pub fn grid_position(self, point: Vec2) -> GridPos {
+        let in_row = |y: i32| {
+            let shift = match self {
+                Topology::Hex if y.rem_euclid(2) == 1 => TILE_SIZE / 2.0,
+                _ => 0.0,
+            };
+            GridPos(((point.x - shift) / TILE_SIZE).floor() as i32, y)
+        };
+
+        let y = (point.y / TILE_SIZE).floor() as i32;
+        match self {
+            Topology::Square => in_row(y),
+            // The pointed ends reach into the rows next to the cell's own
+            Topology::Hex => [y - 1, y, y + 1]
+                .map(in_row)
+                .into_iter()
+                .min_by(|&a, &b| {
+                    let distance =
+                        |position| self.world_position(position).0.distance_squared(point);
+                    distance(a).total_cmp(&distance(b))
+                })
+                .unwrap_or_else(|| in_row(y)),
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use crate::*;
+
+/// Fog of war settings. Without them every player sees the whole board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FogOfWar {
+    /// How many steps each owned tile sees. Tiles on a mountain see
+    /// [`MOUNTAIN_VISION_BONUS`] further, water blocks nothing.
+    pub range: usize,
+}
+
+/// The tiles one player can currently see, sent to them in
+/// [`ClientEvent::Vision`] so the client can draw the rest as fog.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Vision {
+    pub width: usize,
+    pub height: usize,
+    /// Row-major like [`TileGrid::grid`], without the sentinel.
+    pub visible: Vec<bool>,
+}
+
+impl Vision {
+    /// Everything `player` owns and whatever lies within range of it.
+    pub fn new(grid: &TileGrid, player: Player, fog: &FogOfWar) -> Self {
+        let mut visible = vec![false; grid.width * grid.height];
+
+        for (i, tile) in grid.get_tiles().enumerate() {
+            if tile.owner() != Some(player) {
+                continue;
+            }
+
+            let range = match tile.terrain() {
+                Terrain::Mountain => fog.range + MOUNTAIN_VISION_BONUS,
+                _ => fog.range,
+            };
+            let center = grid.get_pos_from_index(i);
+            for position in grid.topology.within(center, range) {
+                if grid.in_bounds_index(&position) {
+                    visible[grid.get_index(position)] = true;
+                }
+            }
+        }
+
+        Self {
+            width: grid.width,
+            height: grid.height,
+            visible,
+        }
+    }
+
+    pub fn contains(&self, position: GridPos) -> bool {
+        let GridPos(x, y) = position - grid_origin(self.width, self.height);
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return false;
+        }
+
+        self.visible[y as usize * self.width + x as usize]
+    }
+
+    /// `grid` as this player may know it. Terrain is part of the map and
+    /// stays, whatever stands on a hidden tile doesn't.
+    pub fn mask(&self, grid: &TileGrid) -> TileGrid {
+        let mut masked = grid.clone();
+        for (i, tile) in masked.grid.iter_mut().enumerate().take(self.visible.len()) {
+            if !self.visible[i] {
+                *tile = TileType::Empty(tile.terrain());
+            }
+        }
+        masked
+    }
+}
+
+impl GameState {
+    /// What `player` sees, or `None` if they see the whole board: there's
+    /// no fog, they are out of the match or the match is over.
+    pub fn vision(&self, player: Player) -> Option<Vision> {
+        let fog = self.fog.as_ref()?;
+        if self.winner().is_some() || !self.players.contains(&player) {
+            return None;
+        }
+
+        Some(Vision::new(&self.grid, player, fog))
+    }
+
+    /// Every player's vision, indexed by `Player as usize`. Taken before an
+    /// action so [`GameState::filter_events`] knows what came into view.
+    pub fn visions(&self) -> Vec<Option<Vision>> {
+        self.map_config.players().map(|p| self.vision(p)).collect()
+    }
+
+    /// `events` as `player` should receive them. Hidden tiles are masked out
+    /// of [`ClientEvent::Init`] and [`ClientEvent::TileChanges`], tiles that
+    /// came into view since `before` are sent along, and a
+    /// [`ClientEvent::Vision`] follows whenever the board was touched.
+    /// Opponents' stockpiles and action points are left out, as they give
+    /// away how many farms stand in the fog.
+    pub fn filter_events(
+        &self,
+        player: Player,
+        before: Option<&Vision>,
+        events: &[ClientEvent],
+    ) -> Vec<ClientEvent> {
+        let vision = self.vision(player);
+        let touches_board = events
+            .iter()
+            .any(|e| matches!(e, ClientEvent::Init(..) | ClientEvent::TileChanges(_)));
+
+        let Some(vision) = vision else {
+            let mut events = events.to_vec();
+            // The fog lifted, e.g. because the match ended
+            if before.is_some() && touches_board {
+                events.insert(0, ClientEvent::TileChanges(self.all_tiles(|_| true)));
+            }
+            return events;
+        };
+
+        let mut filtered = Vec::with_capacity(events.len() + 2);
+        for event in events {
+            match event {
+                ClientEvent::Init(config, grid, rules) => filtered.push(ClientEvent::Init(
+                    config.clone(),
+                    Box::new(vision.mask(grid)),
+                    rules.clone(),
+                )),
+                ClientEvent::TileChanges(changes) => {
+                    // Changes are seen where they happened in view, even if
+                    // the tile is lost from sight by them. Tiles that just
+                    // came into view are sent whole.
+                    let was_visible = |p| before.is_some_and(|b| b.contains(p));
+                    let changes = changes
+                        .iter()
+                        .filter(|c| was_visible(c.position))
+                        .copied()
+                        .chain(self.all_tiles(|p| vision.contains(p) && !was_visible(p)))
+                        .collect();
+                    filtered.push(ClientEvent::TileChanges(changes));
+                }
+                ClientEvent::Select(position) if !vision.contains(*position) => (),
+                ClientEvent::Resources { balance, income } => {
+                    let own = |values: &Vec<Option<usize>>| {
+                        let mut hidden = vec![None; values.len()];
+                        if let Some(&value) = values.get(player as usize) {
+                            hidden[player as usize] = value;
+                        }
+                        hidden
+                    };
+                    filtered.push(ClientEvent::Resources {
+                        balance: own(balance),
+                        income: own(income),
+                    });
+                }
+                // Always about the player on turn once the action is done
+                ClientEvent::ActionPoints(_) if self.turn != player => (),
+                event => filtered.push(event.clone()),
+            }
+        }
+
+        if touches_board {
+            filtered.push(ClientEvent::Vision(vision));
+        }
+        filtered
+    }
+
+    fn all_tiles(&self, include: impl Fn(GridPos) -> bool) -> Vec<TileChange> {
+        self.grid
+            .get_tiles()
+            .enumerate()
+            .map(|(i, &tile)| TileChange {
+                position: self.grid.get_pos_from_index(i),
+                tile,
+            })
+            .filter(|change| include(change.position))
+            .collect()
+    }
+}
@@ -0,0 +1,61 @@
+use store::*;
+
+mod common;
+use common::*;
+
+/// A 7x3 board where red holds the top row and blue the bottom one, with a
+/// farm in between. Blue's base is down to its last HP behind an empty
+/// tile, so red wins this turn by taking that tile first and the base
+/// second, but nothing comes of taking the tile alone.
+fn base_behind_a_gap() -> GameState {
+    let mut state = seated(MapConfig::new(7, 3));
+    state.skip_terrain_placement();
+    for x in -2..=3 {
+        place(&mut state, GridPos(x, -1), PlayerTile::Tile, Player::Red, 1);
+    }
+    for x in -2..=2 {
+        place(&mut state, GridPos(x, 1), PlayerTile::Tile, Player::Blue, 1);
+    }
+    place(&mut state, GridPos(0, 0), PlayerTile::Farm, Player::Blue, 1);
+    state.grid.get_tile_mut(GridPos(3, 1)).damage(1);
+    state
+}
+
+/// Lets `level` play red's whole turn.
+fn play_turn(state: &mut GameState, level: AiLevel) {
+    while state.turn == Player::Red && state.winner().is_none() {
+        let event = choose_event(state, Player::Red, level).unwrap();
+        play(state, event);
+    }
+}
+
+#[test]
+fn search_plays_out_its_turn_before_judging_a_move() {
+    let mut state = base_behind_a_gap();
+
+    play_turn(&mut state, AiLevel::Search);
+
+    assert_eq!(state.winner(), Some(Player::Red));
+}
+
+#[test]
+fn greedy_only_looks_one_move_ahead() {
+    let mut state = base_behind_a_gap();
+
+    play_turn(&mut state, AiLevel::Greedy);
+
+    assert_eq!(state.winner(), None);
+}
+
+#[test]
+fn search_looks_as_many_turns_ahead_as_asked() {
+    let state = base_behind_a_gap();
+    let now = evaluate(&state, Player::Red);
+
+    assert_eq!(search(&state, Player::Red, 0), now);
+    // Red's turn is enough to win, whatever blue would have replied
+    let won = search(&state, Player::Red, 1);
+    assert!(won > now);
+    assert_eq!(search(&state, Player::Red, SEARCH_DEPTH), won);
+    assert_eq!(search(&state, Player::Blue, 1), -won);
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use bevy::prelude::KeyCode;
+use store::*;
+
+mod common;
+use common::*;
+
+const BANK: Duration = Duration::from_secs(10);
+
+/// Lets the bank of the player on turn run dry and plays the timeout.
+fn time_out(state: &mut GameState) -> Vec<ClientEvent> {
+    let action = state.tick(BANK + Duration::from_secs(1)).unwrap();
+    state.consume(&action)
+}
+
+fn end_placement(state: &mut GameState, client_id: u64) -> Vec<ClientEvent> {
+    play(
+        state,
+        terrain(
+            client_id,
+            GridPos(0, 0),
+            GameInput::Keyboard(KeyCode::Return),
+        ),
+    )
+}
+
+#[test]
+fn timeouts_in_terrain_placement_start_the_game_once() {
+    let mut state = seated(MapConfig::with_players(6, 4, 4));
+    state.set_time_control(TimeControl::Bank {
+        initial: BANK,
+        increment: Duration::ZERO,
+    });
+
+    let mut events = time_out(&mut state);
+    assert_eq!(
+        state.players,
+        vec![Player::Blue, Player::Green, Player::Yellow]
+    );
+    assert_eq!(state.turn, Player::Blue);
+
+    events.extend(end_placement(&mut state, BLUE));
+    events.extend(end_placement(&mut state, GREEN));
+    assert_eq!(state.turn, Player::Yellow);
+    assert_eq!(state.game_phase, GamePhase::TerrainPlacement);
+
+    events.extend(time_out(&mut state));
+    assert_eq!(state.players, vec![Player::Blue, Player::Green]);
+    assert_eq!(state.game_phase, GamePhase::Game);
+    assert_eq!(state.turn, Player::Blue);
+    assert_eq!(state.game_turn, 1);
+
+    let game_starts = events
+        .iter()
+        .filter(|&event| *event == ClientEvent::GamePhase(ClientState::Game))
+        .count();
+    assert_eq!(game_starts, 1);
+}
@@ -0,0 +1,90 @@
+//! Seats, boards and inputs shared by the integration tests.
+
+// Every test crate builds its own copy and only uses part of it
+#![allow(dead_code)]
+
+use bevy::prelude::MouseButton;
+use store::*;
+
+pub const RED: u64 = 10;
+pub const BLUE: u64 = 20;
+pub const GREEN: u64 = 30;
+pub const YELLOW: u64 = 40;
+
+/// Client ids of the seats, in [`Player`] order.
+pub const CLIENTS: [u64; MAX_PLAYERS] = [RED, BLUE, GREEN, YELLOW];
+
+pub fn input(client_id: u64, position: GridPos, action: GameInput) -> TileEvent {
+    TileEvent::TileAction {
+        client_id,
+        position,
+        action,
+    }
+}
+
+pub fn click(client_id: u64, position: GridPos, button: MouseButton) -> TileEvent {
+    input(client_id, position, GameInput::Mouse(button))
+}
+
+pub fn select(client_id: u64, position: GridPos) -> TileEvent {
+    TileEvent::ToggleSelect {
+        client_id,
+        position,
+    }
+}
+
+pub fn terrain(client_id: u64, position: GridPos, action: GameInput) -> TileEvent {
+    TileEvent::TerrainAction {
+        client_id,
+        position,
+        action,
+    }
+}
+
+pub fn end_turn(client_id: u64) -> TileEvent {
+    TileEvent::EndTurn { client_id }
+}
+
+/// Plays `event`, which the rules have to accept.
+pub fn play(state: &mut GameState, event: TileEvent) -> Vec<ClientEvent> {
+    let action = state.get_action(&event).unwrap();
+    state.consume(&action)
+}
+
+/// A new match on `map_config` with every seat taken, still in terrain
+/// placement.
+pub fn seated(map_config: MapConfig) -> GameState {
+    seated_with_rules(map_config, RuleSet::default())
+}
+
+pub fn seated_with_rules(map_config: MapConfig, rules: RuleSet) -> GameState {
+    let mut state = GameState::with_rules(map_config, rules);
+    for (player, client_id) in state.map_config.players().zip(CLIENTS).collect::<Vec<_>>() {
+        state.set_player_id(client_id, player);
+    }
+    state
+}
+
+/// A one row board with red's base on the left and blue's on the right,
+/// ready to play.
+pub fn row(width: usize) -> GameState {
+    let mut state = seated(MapConfig::new(width, 1));
+    state.skip_terrain_placement();
+    state
+}
+
+/// Puts an undamaged `player_tile` of `owner` at `level` on `position`,
+/// keeping the terrain under it.
+pub fn place(
+    state: &mut GameState,
+    position: GridPos,
+    player_tile: PlayerTile,
+    owner: Player,
+    level: usize,
+) {
+    let tile = state.grid.get_tile(position);
+    let hp = state.rules.levels.get(player_tile, level).unwrap().hp;
+    state
+        .grid
+        .set_tile(position, tile.with_owner(player_tile, owner, level, hp));
+}
@@ -0,0 +1,54 @@
+use proptest::prelude::*;
+use store::*;
+
+/// A board with every cell other than the bases randomly left empty or
+/// given to one of the players.
+fn grid() -> impl Strategy<Value = TileGrid> {
+    (3..12usize, 2..10usize, 2..=MAX_PLAYERS, any::<bool>())
+        .prop_flat_map(|(width, height, players, hex)| {
+            let owners = proptest::collection::vec(0..=players, width * height);
+            (Just((width, height, players, hex)), owners)
+        })
+        .prop_map(|((width, height, players, hex), owners)| {
+            let config = MapConfig {
+                topology: if hex { Topology::Hex } else { Topology::Square },
+                ..MapConfig::with_players(width, height, players)
+            };
+            let mut grid = TileGrid::new(&config, &RuleSet::default());
+            for (i, owner) in owners.into_iter().enumerate() {
+                let position = grid.get_pos_from_index(i);
+                if owner < players && grid.get_tile(position) == TileType::EMPTY {
+                    grid.set_tile(
+                        position,
+                        TileType::EMPTY.with_owner(PlayerTile::Tile, Player::from(owner), 1, 1),
+                    );
+                }
+            }
+            grid
+        })
+}
+
+proptest! {
+    #[test]
+    fn update_removes_exactly_the_tiles_cut_off_from_base(grid in grid()) {
+        let mut updated = grid.clone();
+        let changes = updated.update();
+
+        for (i, tile) in grid.get_tiles().enumerate() {
+            let position = grid.get_pos_from_index(i);
+            let cut_off = match tile {
+                TileType::Occupied { owner, player_tile, .. } if *player_tile != PlayerTile::Base => {
+                    !grid.is_connected_to_base(position, *owner)
+                }
+                _ => false,
+            };
+
+            let expected = if cut_off { TileType::EMPTY } else { *tile };
+            prop_assert_eq!(updated.get_tile(position), expected);
+            prop_assert_eq!(
+                changes.iter().any(|change| change.position == position),
+                cut_off
+            );
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use bevy::prelude::MouseButton;
+use store::*;
+
+mod common;
+use common::*;
+
+#[test]
+fn farms_pay_out_at_the_start_of_their_owners_turn() {
+    let mut state = row(6);
+    let rules = RuleSet::default();
+    let costs = rules.costs;
+    assert_eq!(
+        state.economy.balance(Player::Red),
+        rules.starting_resources + rules.base_income
+    );
+
+    play(&mut state, click(RED, GridPos(-2, 0), MouseButton::Left));
+    play(&mut state, end_turn(RED));
+    play(&mut state, click(BLUE, GridPos(1, 0), MouseButton::Left));
+    play(&mut state, end_turn(BLUE));
+    play(&mut state, click(RED, GridPos(-2, 0), MouseButton::Right));
+    play(&mut state, end_turn(RED));
+    assert!(state.grid.get_tile(GridPos(-2, 0)).is_farm());
+    assert_eq!(
+        Economy::income(&rules, &state.grid, Player::Red),
+        rules.base_income + rules.farm_income
+    );
+
+    let before = state.economy.balance(Player::Red);
+    play(&mut state, click(BLUE, GridPos(0, 0), MouseButton::Left));
+    let events = play(&mut state, end_turn(BLUE));
+    assert_eq!(
+        state.economy.balance(Player::Red),
+        before + rules.base_income + rules.farm_income
+    );
+    assert!(events.contains(&state.resources_event()));
+    assert_eq!(
+        before,
+        rules.starting_resources + 2 * rules.base_income - costs.attack - costs.make_farm
+    );
+}
+
+#[test]
+fn actions_are_refused_once_the_stockpile_runs_dry() {
+    let mut state = row(6);
+    state.economy.balance[Player::Red as usize] = CostTable::DEFAULT.attack - 1;
+
+    assert_eq!(
+        state.get_action(&click(RED, GridPos(-2, 0), MouseButton::Left)),
+        Err(RuleError::NotEnoughResources {
+            needed: CostTable::DEFAULT.attack,
+            available: CostTable::DEFAULT.attack - 1,
+        })
+    );
+    assert_eq!(state.legal_actions(Player::Red), vec![GameAction::EndTurn]);
+}
+
+#[test]
+fn farms_earn_extra_action_points() {
+    let mut state = row(6);
+    let rules = RuleSet::default();
+    assert_eq!(state.action_points, rules.action_points);
+
+    let farm = state.grid.get_tile(GridPos(-2, 0)).with_owner(
+        PlayerTile::Farm,
+        Player::Red,
+        rules.farms_per_action_point,
+        1,
+    );
+    state.grid.set_tile(GridPos(-2, 0), farm);
+    assert_eq!(
+        state.action_points_per_turn(Player::Red),
+        rules.action_points + 1
+    );
+
+    // Blue runs out of points and hands the turn back on its own
+    play(&mut state, end_turn(RED));
+    for _ in 0..rules.action_points {
+        assert_eq!(state.turn, Player::Blue);
+        play(&mut state, click(BLUE, GridPos(1, 0), MouseButton::Left));
+    }
+    assert_eq!(state.turn, Player::Red);
+    assert_eq!(state.action_points, rules.action_points + 1);
+}
@@ -0,0 +1,73 @@
+use store::*;
+
+mod common;
+use common::*;
+
+/// A long row under fog, too wide for either player to see the other's
+/// half, with a farm on each side.
+fn fogged_row() -> GameState {
+    let mut state = row(11);
+    state.fog = Some(FogOfWar { range: 1 });
+    place(&mut state, GridPos(-4, 0), PlayerTile::Farm, Player::Red, 1);
+    place(&mut state, GridPos(4, 0), PlayerTile::Farm, Player::Blue, 1);
+    state
+}
+
+/// Plays `event` and returns what each seat receives, indexed by
+/// `Player as usize`.
+fn play_filtered(state: &mut GameState, event: TileEvent) -> Vec<Vec<ClientEvent>> {
+    let before = state.visions();
+    let events = play(state, event);
+    state
+        .map_config
+        .players()
+        .map(|p| state.filter_events(p, before[p as usize].as_ref(), &events))
+        .collect()
+}
+
+#[test]
+fn fogged_clients_only_receive_their_own_stockpile() {
+    let mut state = fogged_row();
+
+    for client_id in [RED, BLUE, RED, BLUE] {
+        for (i, events) in play_filtered(&mut state, end_turn(client_id))
+            .into_iter()
+            .enumerate()
+        {
+            let resources = events.iter().find_map(|e| match e {
+                ClientEvent::Resources { balance, income } => Some((balance, income)),
+                _ => None,
+            });
+            let (balance, income) = resources.unwrap();
+            for (j, (balance, income)) in balance.iter().zip(income).enumerate() {
+                assert_eq!(balance.is_some(), i == j);
+                assert_eq!(income.is_some(), i == j);
+            }
+        }
+    }
+}
+
+#[test]
+fn fogged_clients_only_receive_their_own_action_points() {
+    let mut state = fogged_row();
+
+    let events = play_filtered(&mut state, end_turn(RED));
+
+    let action_points = |events: &[ClientEvent]| {
+        events
+            .iter()
+            .any(|e| matches!(e, ClientEvent::ActionPoints(_)))
+    };
+    assert!(!action_points(&events[Player::Red as usize]));
+    assert!(action_points(&events[Player::Blue as usize]));
+}
+
+#[test]
+fn without_fog_every_stockpile_is_sent() {
+    let mut state = fogged_row();
+    state.fog = None;
+
+    for events in play_filtered(&mut state, end_turn(RED)) {
+        assert!(events.contains(&state.resources_event()));
+    }
+}
@@ -0,0 +1,66 @@
+use bevy::prelude::MouseButton;
+use store::*;
+
+mod common;
+use common::*;
+
+/// A 5x3 board for red, blue and green. Red holds the top row up to a tile
+/// next to blue's base, green holds the tile under red's base, and both
+/// bases are down to their last HP.
+fn three_players() -> GameState {
+    let mut state = seated(MapConfig::with_players(5, 3, 3));
+    state.skip_terrain_placement();
+    for x in -1..=2 {
+        place(&mut state, GridPos(x, -1), PlayerTile::Tile, Player::Red, 1);
+    }
+    place(
+        &mut state,
+        GridPos(-2, 0),
+        PlayerTile::Tile,
+        Player::Green,
+        1,
+    );
+    state.grid.get_tile_mut(GridPos(-2, -1)).damage(1);
+    state.grid.get_tile_mut(GridPos(2, 1)).damage(1);
+    state
+}
+
+#[test]
+fn captured_bases_become_tiles() {
+    let mut state = three_players();
+
+    play(&mut state, click(RED, GridPos(2, 0), MouseButton::Left));
+    let events = play(&mut state, click(RED, GridPos(2, 1), MouseButton::Left));
+
+    // Blue held nothing but the base
+    assert!(events.contains(&ClientEvent::Eliminated {
+        player: Player::Blue,
+        reason: GameOverReason::NoTilesLeft,
+    }));
+    assert_eq!(
+        state.grid.get_tile(GridPos(2, 1)).player_tile(),
+        Some(PlayerTile::Tile)
+    );
+    assert_eq!(
+        state.grid.get_tile(GridPos(2, 1)).owner(),
+        Some(Player::Red)
+    );
+}
+
+#[test]
+fn a_captured_base_does_not_keep_its_new_owner_alive() {
+    let mut state = three_players();
+    play(&mut state, click(RED, GridPos(2, 0), MouseButton::Left));
+    play(&mut state, click(RED, GridPos(2, 1), MouseButton::Left));
+    assert_eq!(state.turn, Player::Green);
+
+    play(&mut state, click(GREEN, GridPos(-2, -1), MouseButton::Left));
+
+    assert_eq!(
+        state.game_phase,
+        GamePhase::Finished {
+            winner: Player::Green,
+            reason: GameOverReason::BaseDestroyed,
+        }
+    );
+}
@@ -0,0 +1,106 @@
+use std::collections::{HashSet, VecDeque};
+
+use store::*;
+
+const SEEDS: std::ops::Range<u64> = 0..32;
+
+/// Boards of several shapes and player counts, square and hex.
+fn configs() -> Vec<MapConfig> {
+    let mut configs = Vec::new();
+    for (width, height, players) in [(16, 8, 2), (9, 5, 2), (7, 7, 3), (10, 6, 4), (12, 4, 4)] {
+        for topology in [Topology::Square, Topology::Hex] {
+            configs.push(MapConfig {
+                topology,
+                ..MapConfig::with_players(width, height, players)
+            });
+        }
+    }
+    configs
+}
+
+/// The cell opposite `position` through the centre of the board.
+fn mirror(config: &MapConfig, position: GridPos) -> GridPos {
+    let min = grid_origin(config.width, config.height);
+    let max = min + GridPos(config.width as i32 - 1, config.height as i32 - 1);
+    min + max - position
+}
+
+/// Every cell reachable from `start` without crossing water.
+fn reachable(config: &MapConfig, start: GridPos) -> HashSet<GridPos> {
+    let min = grid_origin(config.width, config.height);
+    let on_board = |GridPos(x, y): GridPos| {
+        x >= min.0
+            && y >= min.1
+            && x < min.0 + config.width as i32
+            && y < min.1 + config.height as i32
+    };
+
+    let mut reached = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(position) = queue.pop_front() {
+        for next in config.topology.neighbours(position) {
+            if on_board(next) && !config.water.contains(&next) && reached.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    reached
+}
+
+#[test]
+fn the_same_seed_gives_the_same_map() {
+    for config in configs() {
+        for seed in SEEDS {
+            assert_eq!(
+                generate_terrain(&config, seed),
+                generate_terrain(&config, seed)
+            );
+        }
+    }
+}
+
+#[test]
+fn seeds_make_a_difference() {
+    for config in configs() {
+        let maps = SEEDS
+            .map(|seed| generate_terrain(&config, seed))
+            .collect::<Vec<_>>();
+
+        assert!(maps.iter().any(|map| !map.water.is_empty()));
+        assert!(maps.iter().any(|map| !map.mountains.is_empty()));
+        assert!(maps.iter().any(|map| *map != maps[0]));
+    }
+}
+
+#[test]
+fn terrain_is_mirrored_through_the_centre() {
+    for config in configs() {
+        for seed in SEEDS {
+            let map = generate_terrain(&config, seed);
+
+            for &water in &map.water {
+                assert!(map.water.contains(&mirror(&map, water)), "{:?}", map);
+            }
+            for &mountain in &map.mountains {
+                assert!(map.mountains.contains(&mirror(&map, mountain)), "{:?}", map);
+            }
+        }
+    }
+}
+
+#[test]
+fn every_base_can_reach_every_other_base() {
+    for config in configs() {
+        for seed in SEEDS {
+            let map = generate_terrain(&config, seed);
+
+            for &base in &map.bases {
+                let reached = reachable(&map, base);
+                for other in &map.bases {
+                    assert!(reached.contains(other), "{:?}", map);
+                }
+            }
+            assert_eq!(map.validate(), Ok(()));
+        }
+    }
+}
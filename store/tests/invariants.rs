@@ -0,0 +1,200 @@
+use bevy::prelude::Vec2;
+use proptest::prelude::*;
+use store::*;
+
+mod common;
+use common::*;
+
+/// One thing that happens to a match: a raw client input, which the rules
+/// may well refuse, or a pick among the moves the rules accept so games get
+/// past the opening.
+#[derive(Debug, Clone)]
+enum Step {
+    Input(TileEvent),
+    Legal(usize),
+}
+
+fn map_config() -> impl Strategy<Value = MapConfig> {
+    (
+        3..10usize,
+        2..7usize,
+        2..=MAX_PLAYERS,
+        any::<bool>(),
+        any::<Option<u64>>(),
+    )
+        .prop_map(|(width, height, players, hex, seed)| {
+            let config = MapConfig {
+                topology: if hex { Topology::Hex } else { Topology::Square },
+                ..MapConfig::with_players(width, height, players)
+            };
+            match seed {
+                Some(seed) => generate_terrain(&config, seed),
+                None => config,
+            }
+        })
+}
+
+fn tile_event() -> impl Strategy<Value = TileEvent> {
+    // Every seat's id and two spectators
+    let client_id = proptest::sample::select([&CLIENTS[..], &[0, 99]].concat());
+    let position = (-7..7, -5..5).prop_map(|(x, y)| GridPos(x, y));
+    let input = proptest::sample::select(&GameInput::ALL[..]);
+
+    prop_oneof![
+        (client_id.clone(), position.clone(), input.clone()).prop_map(
+            |(client_id, position, action)| TileEvent::TileAction {
+                client_id,
+                position,
+                action,
+            }
+        ),
+        (client_id.clone(), position.clone()).prop_map(|(client_id, position)| {
+            TileEvent::ToggleSelect {
+                client_id,
+                position,
+            }
+        }),
+        client_id
+            .clone()
+            .prop_map(|client_id| TileEvent::EndTurn { client_id }),
+        (client_id, position, input).prop_map(|(client_id, position, action)| {
+            TileEvent::TerrainAction {
+                client_id,
+                position,
+                action,
+            }
+        }),
+        Just(TileEvent::None),
+    ]
+}
+
+fn steps() -> impl Strategy<Value = Vec<Step>> {
+    let step = prop_oneof![
+        tile_event().prop_map(Step::Input),
+        any::<usize>().prop_map(Step::Legal),
+    ];
+    proptest::collection::vec(step, 0..200)
+}
+
+fn assert_invariants(state: &GameState) -> Result<(), TestCaseError> {
+    let grid = &state.grid;
+
+    for (i, tile) in grid.get_tiles().enumerate() {
+        if let TileType::Occupied {
+            player_tile: PlayerTile::Farm | PlayerTile::Tile | PlayerTile::Tower,
+            owner,
+            ..
+        } = *tile
+        {
+            let position = grid.get_pos_from_index(i);
+            prop_assert!(
+                grid.is_connected_to_base(position, owner),
+                "{}'s tile at {} is cut off from their base",
+                owner,
+                position
+            );
+        }
+    }
+
+    prop_assert_eq!(state.economy.balance.len(), state.map_config.player_count());
+
+    prop_assert!(state.players.contains(&state.turn) || state.winner().is_some());
+    prop_assert_eq!(state.players.len() == 1, state.winner().is_some());
+
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn rules_keep_their_invariants(
+        map_config in map_config(),
+        skip_placement in any::<bool>(),
+        steps in steps(),
+    ) {
+        let mut state = seated(map_config);
+        if skip_placement {
+            state.skip_terrain_placement();
+        }
+        assert_invariants(&state)?;
+
+        for step in steps {
+            let action = match step {
+                Step::Input(event) => state.get_action(&event).ok(),
+                Step::Legal(pick) => {
+                    let legal = state.legal_actions(state.turn);
+                    (!legal.is_empty()).then(|| legal[pick % legal.len()].clone())
+                }
+            };
+
+            if let Some(action) = action {
+                let cost = state.cost(&action);
+                let balance = state.economy.balance(state.turn);
+                prop_assert!(cost <= balance, "{:?} costs {} out of {}", action, cost, balance);
+
+                state.consume(&action);
+                assert_invariants(&state)?;
+            }
+        }
+    }
+
+    #[test]
+    fn out_of_bounds_grid_positions_hit_the_sentinel(
+        map_config in map_config(),
+        x in -20..20i32,
+        y in -20..20i32,
+    ) {
+        let grid = TileGrid::new(&map_config, &RuleSet::default());
+        let position = GridPos(x, y);
+        let index = grid.get_index(position);
+
+        if grid.in_bounds(x, y) {
+            prop_assert!(index < grid.grid.len() - 1);
+            prop_assert_eq!(grid.get_pos_from_index(index), position);
+        } else {
+            prop_assert_eq!(index, grid.grid.len() - 1);
+        }
+    }
+
+    #[test]
+    fn out_of_bounds_world_positions_hit_the_sentinel(
+        map_config in map_config(),
+        x in -1000.0..1000.0f32,
+        y in -1000.0..1000.0f32,
+    ) {
+        let grid = TileGrid::new(&map_config, &RuleSet::default());
+        let min = grid.origin();
+
+        // Tile `i` covers `i * TILE_SIZE` up to `(i + 1) * TILE_SIZE`, half a
+        // tile further on shifted hex rows. Stay clear of the edges where
+        // rounding decides
+        let outside = |value: f32, min: i32, len: usize, shift: f32| {
+            value < min as f32 * TILE_SIZE - 1.0
+                || value > (min + len as i32) as f32 * TILE_SIZE + shift + 1.0
+        };
+        prop_assume!(
+            outside(x, min.0, grid.width, TILE_SIZE / 2.0)
+                || outside(y, min.1, grid.height, 0.0)
+        );
+
+        let index = grid.get_index_from_position(&Position(Vec2::new(x, y)));
+        prop_assert_eq!(index, grid.grid.len() - 1);
+    }
+
+    #[test]
+    fn tile_centres_map_back_to_their_tile(
+        map_config in map_config(),
+        x in -20..20i32,
+        y in -20..20i32,
+    ) {
+        let grid = TileGrid::new(&map_config, &RuleSet::default());
+        let position = GridPos(x, y);
+
+        let centre = grid.topology.world_position(position);
+
+        prop_assert_eq!(grid.topology.grid_position(centre.0), position);
+        prop_assert_eq!(
+            grid.get_index_from_position(&centre),
+            grid.get_index(position)
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use bevy::prelude::MouseButton;
+use store::*;
+
+mod common;
+use common::*;
+
+/// Everything a client in `player`'s seat could send, including clicks just
+/// off the board and inputs sent from the wrong screen.
+fn every_event(state: &GameState, player: Player) -> Vec<TileEvent> {
+    let client_id = state.client_id(player).unwrap();
+    let origin = state.grid.origin();
+    let (width, height) = (state.grid.width as i32, state.grid.height as i32);
+
+    let mut events = Vec::new();
+    for y in origin.1 - 1..=origin.1 + height {
+        for x in origin.0 - 1..=origin.0 + width {
+            for input in GameInput::ALL {
+                for client_state in [ClientState::Terrain, ClientState::Game] {
+                    events.push(TileEvent::from_input(
+                        client_id,
+                        GridPos(x, y),
+                        input,
+                        &client_state,
+                    ));
+                }
+            }
+        }
+    }
+    events
+}
+
+/// Every accepted input maps to an enumerated action and every enumerated
+/// action is reachable by some input.
+fn assert_consistent(state: &GameState) {
+    for player in state.map_config.players() {
+        let legal = state.legal_actions(player);
+
+        let mut accepted = Vec::new();
+        for event in every_event(state, player) {
+            if let Ok(action) = state.get_action(&event) {
+                assert!(
+                    legal.contains(&action),
+                    "{:?} accepted {:?} for {} but it wasn't enumerated",
+                    event,
+                    action,
+                    player
+                );
+                if !accepted.contains(&action) {
+                    accepted.push(action);
+                }
+            }
+        }
+
+        assert_eq!(
+            accepted.len(),
+            legal.len(),
+            "{} has unreachable actions",
+            player
+        );
+        if player != state.turn {
+            assert!(legal.is_empty(), "{} may act out of turn", player);
+        }
+    }
+}
+
+/// Plays greedy moves for everyone, checking every position along the way.
+fn assert_consistent_through_game(mut state: GameState, moves: usize) {
+    for _ in 0..moves {
+        assert_consistent(&state);
+
+        let Some(event) = choose_event(&state, state.turn, AiLevel::Greedy) else {
+            break;
+        };
+        let action = state.get_action(&event).unwrap();
+        state.consume(&action);
+    }
+}
+
+#[test]
+fn terrain_placement_actions() {
+    let state = seated(MapConfig::default());
+    assert_consistent(&state);
+
+    let legal = state.legal_actions(Player::Red);
+    assert!(legal.contains(&GameAction::EndTerrainPlacement));
+    assert!(legal.contains(&GameAction::SetTerrainMode(Terrain::Water)));
+    assert!(legal.contains(&GameAction::MakeTerrain(GridPos(0, 0), Terrain::Mountain)));
+    assert!(!legal
+        .iter()
+        .any(|action| matches!(action, GameAction::MakeTerrain(GridPos(-8, -4), _))));
+}
+
+#[test]
+fn terrain_budget_is_respected() {
+    let state = seated(MapConfig {
+        mountain_budget: Some(0),
+        ..Default::default()
+    });
+    assert_consistent(&state);
+
+    assert!(!state
+        .legal_actions(Player::Red)
+        .iter()
+        .any(|action| matches!(action, GameAction::MakeTerrain(_, Terrain::Mountain))));
+}
+
+#[test]
+fn opening_attacks() {
+    let mut state = seated(MapConfig::default());
+    state.skip_terrain_placement();
+    assert_consistent(&state);
+
+    let legal = state.legal_actions(Player::Red);
+    assert!(legal.contains(&GameAction::Attack(vec![GridPos(-7, -4)])));
+    assert!(legal.contains(&GameAction::Attack(vec![GridPos(-8, -3)])));
+    assert!(!legal
+        .iter()
+        .any(|action| matches!(action, GameAction::MakeFarm(_) | GameAction::Select(_))));
+    assert!(state.legal_actions(Player::Blue).is_empty());
+}
+
+#[test]
+fn selection_changes_attacks() {
+    let mut state = seated(MapConfig::default());
+    state.skip_terrain_placement();
+    // Both sides take a tile and upgrade it, using up their action points
+    for event in [
+        click(RED, GridPos(-7, -4), MouseButton::Left),
+        click(RED, GridPos(-7, -4), MouseButton::Left),
+        click(BLUE, GridPos(6, 3), MouseButton::Left),
+        click(BLUE, GridPos(6, 3), MouseButton::Left),
+    ] {
+        play(&mut state, event);
+    }
+
+    let select = GameAction::Select(GridPos(-7, -4));
+    assert!(state.legal_actions(Player::Red).contains(&select));
+    state.consume(&select);
+    assert_consistent(&state);
+
+    // A level 2 tile hits two tiles in a line
+    let legal = state.legal_actions(Player::Red);
+    assert!(legal.contains(&GameAction::Deselect));
+    assert!(legal.contains(&GameAction::Attack(vec![GridPos(-6, -4), GridPos(-5, -4)])));
+}
+
+#[test]
+fn consistent_through_a_two_player_game() {
+    assert_consistent_through_game(seated(generate_terrain(&MapConfig::default(), 3)), 60);
+}
+
+#[test]
+fn consistent_through_a_four_player_game() {
+    let map_config = MapConfig::with_players(10, 6, 4);
+    assert_consistent_through_game(seated(map_config), 60);
+}
+
+#[test]
+fn nothing_is_legal_once_the_game_is_over() {
+    let mut state = seated(MapConfig::default());
+    state.skip_terrain_placement();
+    state.game_phase = GamePhase::Finished {
+        winner: Player::Red,
+        reason: GameOverReason::BaseDestroyed,
+    };
+
+    assert_consistent(&state);
+    for player in state.map_config.players() {
+        assert!(state.legal_actions(player).is_empty());
+    }
+}
+
+#[test]
+fn empty_seats_have_the_same_moves() {
+    let mut taken = seated(MapConfig::default());
+    let mut empty = GameState::new(MapConfig::default());
+    assert_eq!(
+        empty.legal_actions(Player::Red),
+        taken.legal_actions(Player::Red)
+    );
+
+    taken.skip_terrain_placement();
+    empty.skip_terrain_placement();
+    assert!(!empty.legal_actions(Player::Red).is_empty());
+    assert_eq!(
+        empty.legal_actions(Player::Red),
+        taken.legal_actions(Player::Red)
+    );
+}
@@ -0,0 +1,94 @@
+use bevy::prelude::MouseButton;
+use store::*;
+
+mod common;
+use common::*;
+
+/// A 7x3 board with red's base and blue's in opposite corners.
+fn board() -> GameState {
+    let mut state = seated(MapConfig::new(7, 3));
+    state.skip_terrain_placement();
+    state
+}
+
+#[test]
+fn level_four_tiles_hit_two_in_a_line_and_both_sides() {
+    let mut state = board();
+    place(
+        &mut state,
+        GridPos(-2, -1),
+        PlayerTile::Tile,
+        Player::Red,
+        1,
+    );
+    place(&mut state, GridPos(-2, 0), PlayerTile::Tile, Player::Red, 1);
+    place(&mut state, GridPos(-1, 0), PlayerTile::Tile, Player::Red, 4);
+
+    play(&mut state, select(RED, GridPos(-1, 0)));
+
+    let expected = GameAction::Attack(vec![
+        GridPos(0, 0),
+        GridPos(1, 0),
+        GridPos(0, -1),
+        GridPos(0, 1),
+    ]);
+    assert_eq!(
+        state.get_action(&click(RED, GridPos(0, 0), MouseButton::Left)),
+        Ok(expected.clone())
+    );
+    assert_eq!(
+        state.get_action(&click(RED, GridPos(1, 0), MouseButton::Left)),
+        Ok(expected)
+    );
+    assert_eq!(
+        state.get_action(&click(RED, GridPos(2, 0), MouseButton::Left)),
+        Err(RuleError::TargetNotAdjacent)
+    );
+}
+
+#[test]
+fn upgrades_stop_at_the_max_level() {
+    let mut state = board();
+    place(
+        &mut state,
+        GridPos(-2, -1),
+        PlayerTile::Tile,
+        Player::Red,
+        3,
+    );
+    place(
+        &mut state,
+        GridPos(-1, -1),
+        PlayerTile::Tile,
+        Player::Red,
+        4,
+    );
+    place(&mut state, GridPos(-3, 0), PlayerTile::Farm, Player::Red, 3);
+    state.economy.balance[Player::Red as usize] = 100;
+
+    assert_eq!(
+        state.get_action(&click(RED, GridPos(-2, -1), MouseButton::Left)),
+        Ok(GameAction::Upgrade(GridPos(-2, -1)))
+    );
+    assert_eq!(
+        state.get_action(&click(RED, GridPos(-1, -1), MouseButton::Left)),
+        Err(RuleError::MaxLevel)
+    );
+    assert_eq!(
+        state.get_action(&click(RED, GridPos(-3, 0), MouseButton::Left)),
+        Err(RuleError::MaxLevel)
+    );
+}
+
+#[test]
+fn upgrading_keeps_damage_taken() {
+    let levels = LevelTable::default();
+    let damaged = TileType::EMPTY.with_owner(PlayerTile::Tile, Player::Red, 3, 1);
+
+    let upgraded = levels.upgraded(damaged).unwrap();
+    assert_eq!(
+        upgraded,
+        TileType::EMPTY.with_owner(PlayerTile::Tile, Player::Red, 4, 2)
+    );
+    assert_eq!(levels.upgraded(upgraded), None);
+}
@@ -0,0 +1,152 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use store::*;
+
+fn river() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../maps/river.json")
+}
+
+/// Writes `json` to a map file of its own and loads it.
+fn load(name: &str, json: &str) -> anyhow::Result<MapConfig> {
+    let path = std::env::temp_dir().join(format!("store-{}-{}.json", std::process::id(), name));
+    fs::write(&path, json).unwrap();
+    let config = MapConfig::load_from_path(&path);
+    fs::remove_file(&path).unwrap();
+    config
+}
+
+/// A plain 8x4 board, bases at (-4, -2) and (3, 1).
+fn board() -> MapConfig {
+    MapConfig::new(8, 4)
+}
+
+#[test]
+fn map_files_round_trip() {
+    let config = MapConfig::load_from_path(&river()).unwrap();
+
+    assert_eq!((config.width, config.height), (16, 8));
+    assert_eq!(config.bases, vec![GridPos(-8, -4), GridPos(7, 3)]);
+    assert_eq!(config.neutral, vec![GridPos(-1, -1), GridPos(0, 0)]);
+    assert_eq!(config.topology, Topology::Square);
+
+    let json = serde_json::to_string(&config).unwrap();
+    assert_eq!(load("round-trip", &json).unwrap(), config);
+}
+
+#[test]
+fn maps_override_the_terrain_budgets() {
+    let config = MapConfig::load_from_path(&river()).unwrap();
+    let state = GameState::new(config);
+
+    assert_eq!(state.rules.mountain_budget, 2);
+    assert_eq!(state.rules.water_budget, 2);
+    assert_ne!(RuleSet::default().mountain_budget, 2);
+    assert_ne!(RuleSet::default().water_budget, 2);
+}
+
+#[test]
+fn malformed_map_files_are_refused() {
+    assert!(load(
+        "truncated",
+        r#"{"width": 8, "height": 4, "bases": [[-4, -2]"#
+    )
+    .is_err());
+    assert!(load("missing-bases", r#"{"width": 8, "height": 4}"#).is_err());
+    assert!(MapConfig::load_from_path(Path::new("no/such/map.json")).is_err());
+}
+
+#[test]
+fn loaded_maps_are_validated() {
+    let error = load(
+        "out-of-bounds",
+        r#"{"width": 8, "height": 4, "bases": [[-4, -2], [4, 1]]}"#,
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        error.downcast_ref::<MapError>(),
+        Some(&MapError::OutOfBounds(GridPos(4, 1)))
+    );
+}
+
+#[test]
+fn maps_need_tiles() {
+    let config = MapConfig {
+        width: 0,
+        ..board()
+    };
+    assert_eq!(config.validate(), Err(MapError::Empty));
+}
+
+#[test]
+fn maps_need_two_to_four_bases() {
+    let mut config = board();
+    config.bases.truncate(1);
+    assert_eq!(config.validate(), Err(MapError::PlayerCount(1)));
+
+    let config = MapConfig {
+        bases: (-4..1).map(|x| GridPos(x, 0)).collect(),
+        ..board()
+    };
+    assert_eq!(config.validate(), Err(MapError::PlayerCount(5)));
+}
+
+#[test]
+fn features_must_be_on_the_board() {
+    let config = MapConfig {
+        bases: vec![GridPos(-4, -2), GridPos(3, 2)],
+        ..board()
+    };
+    assert_eq!(config.validate(), Err(MapError::OutOfBounds(GridPos(3, 2))));
+
+    let config = MapConfig {
+        mountains: vec![GridPos(-5, 0)],
+        ..board()
+    };
+    assert_eq!(
+        config.validate(),
+        Err(MapError::OutOfBounds(GridPos(-5, 0)))
+    );
+}
+
+#[test]
+fn features_cant_share_a_tile() {
+    let config = MapConfig {
+        bases: vec![GridPos(-4, -2), GridPos(-4, -2)],
+        ..board()
+    };
+    assert_eq!(
+        config.validate(),
+        Err(MapError::Overlapping(GridPos(-4, -2)))
+    );
+
+    let config = MapConfig {
+        water: vec![GridPos(0, 0)],
+        neutral: vec![GridPos(0, 0)],
+        ..board()
+    };
+    assert_eq!(config.validate(), Err(MapError::Overlapping(GridPos(0, 0))));
+}
+
+#[test]
+fn bases_walled_off_by_water_are_refused() {
+    let config = MapConfig {
+        water: (-2..2).map(|y| GridPos(0, y)).collect(),
+        ..board()
+    };
+    assert_eq!(
+        config.validate(),
+        Err(MapError::BaseUnreachable(Player::Blue))
+    );
+
+    // Mountains can be captured, a wall of them is only slow going
+    let config = MapConfig {
+        water: vec![GridPos(0, -2), GridPos(0, -1)],
+        mountains: vec![GridPos(0, 0), GridPos(0, 1)],
+        ..board()
+    };
+    assert_eq!(config.validate(), Ok(()));
+}
@@ -0,0 +1,190 @@
+use bevy::prelude::{KeyCode, MouseButton};
+use store::*;
+
+mod common;
+use common::*;
+
+/// A one row board with red's base on the left and blue's on the right,
+/// still in terrain placement.
+fn placement_row(mode: Mode) -> GameState {
+    let mut state = seated(MapConfig::new(5, 1));
+    state.set_mode(mode);
+    state
+}
+
+#[test]
+fn classic_games_only_end_by_elimination() {
+    let mut state = placement_row(Mode::Classic);
+    state.skip_terrain_placement();
+    place(&mut state, GridPos(-1, 0), PlayerTile::Tile, Player::Red, 1);
+    place(&mut state, GridPos(0, 0), PlayerTile::Tile, Player::Red, 1);
+
+    for _ in 0..50 {
+        play(&mut state, end_turn(RED));
+        play(&mut state, end_turn(BLUE));
+    }
+    assert_eq!(state.game_phase, GamePhase::Game);
+}
+
+#[test]
+fn the_hill_starts_neutral_and_takes_no_terrain() {
+    let state = placement_row(Mode::KingOfTheHill);
+    let hill = KingOfTheHill::hill(&state.map_config).unwrap();
+
+    assert_eq!(hill, GridPos(0, 0));
+    assert_eq!(
+        state.grid.get_tile(hill),
+        TileType::Neutral {
+            hp: state.rules.neutral_tile_hp
+        }
+    );
+    let make_terrain = terrain(RED, hill, GameInput::Mouse(MouseButton::Left));
+    assert_eq!(
+        state.get_action(&make_terrain),
+        Err(RuleError::TileOccupied)
+    );
+}
+
+#[test]
+fn the_hill_moves_aside_for_the_map() {
+    let mut state = seated(MapConfig {
+        mountains: vec![GridPos(0, 0)],
+        ..MapConfig::new(5, 3)
+    });
+    state.set_mode(Mode::KingOfTheHill);
+    let hill = KingOfTheHill::hill(&state.map_config).unwrap();
+
+    assert_eq!(state.grid.topology.distance(hill, GridPos(0, 0)), 1);
+    assert_eq!(
+        state.grid.get_tile(hill),
+        TileType::Neutral {
+            hp: state.rules.neutral_tile_hp
+        }
+    );
+    assert_eq!(
+        state.grid.get_tile(GridPos(0, 0)),
+        TileType::Empty(Terrain::Mountain)
+    );
+}
+
+#[test]
+fn holding_the_hill_for_a_round_wins() {
+    let mut state = placement_row(Mode::KingOfTheHill);
+    state.skip_terrain_placement();
+    place(&mut state, GridPos(-1, 0), PlayerTile::Tile, Player::Red, 1);
+    place(&mut state, GridPos(0, 0), PlayerTile::Tile, Player::Red, 1);
+
+    play(&mut state, end_turn(RED));
+    assert_eq!(state.game_phase, GamePhase::Game);
+
+    let events = play(&mut state, end_turn(BLUE));
+    let game_over = ClientEvent::GameOver {
+        winner: Player::Red,
+        reason: GameOverReason::HillHeld,
+    };
+    assert!(events.contains(&game_over));
+    assert_eq!(
+        state.game_phase,
+        GamePhase::Finished {
+            winner: Player::Red,
+            reason: GameOverReason::HillHeld
+        }
+    );
+}
+
+#[test]
+fn score_races_go_to_the_most_tiles_at_the_turn_limit() {
+    let mut state = placement_row(Mode::ScoreRace { turns: 4 });
+    state.skip_terrain_placement();
+    place(&mut state, GridPos(1, 0), PlayerTile::Tile, Player::Blue, 1);
+    assert_eq!(state.score(Player::Red), 1);
+    assert_eq!(state.score(Player::Blue), 2);
+
+    play(&mut state, end_turn(RED));
+    play(&mut state, end_turn(BLUE));
+    play(&mut state, end_turn(RED));
+    assert_eq!(state.game_phase, GamePhase::Game);
+
+    play(&mut state, end_turn(BLUE));
+    assert_eq!(
+        state.game_phase,
+        GamePhase::Finished {
+            winner: Player::Blue,
+            reason: GameOverReason::TurnLimit
+        }
+    );
+}
+
+#[test]
+fn terrain_placement_turns_dont_count_towards_the_turn_limit() {
+    let mut state = placement_row(Mode::ScoreRace { turns: 2 });
+    play(
+        &mut state,
+        terrain(RED, GridPos(0, 0), GameInput::Keyboard(KeyCode::Return)),
+    );
+    play(
+        &mut state,
+        terrain(BLUE, GridPos(0, 0), GameInput::Keyboard(KeyCode::Return)),
+    );
+    assert_eq!(state.game_turn, 1);
+
+    play(&mut state, end_turn(RED));
+    assert_eq!(state.game_phase, GamePhase::Game);
+
+    play(&mut state, end_turn(BLUE));
+    assert!(matches!(state.game_phase, GamePhase::Finished { .. }));
+}
+
+#[test]
+fn score_races_parsed_without_a_limit_get_the_default_one() {
+    let mut state = placement_row("score-race".parse().unwrap());
+    assert_eq!(
+        state.mode,
+        Mode::ScoreRace {
+            turns: DEFAULT_TURN_LIMIT
+        }
+    );
+
+    state.skip_terrain_placement();
+    play(&mut state, end_turn(RED));
+    play(&mut state, end_turn(BLUE));
+    assert_eq!(state.game_phase, GamePhase::Game);
+}
+
+#[test]
+fn everyone_starts_with_a_farm_by_their_base() {
+    let state = placement_row(Mode::CaptureAllFarms);
+
+    for (position, owner) in [(GridPos(-1, 0), Player::Red), (GridPos(1, 0), Player::Blue)] {
+        let tile = state.grid.get_tile(position);
+        assert_eq!(tile.player_tile(), Some(PlayerTile::Farm));
+        assert_eq!(tile.owner(), Some(owner));
+    }
+}
+
+#[test]
+fn holding_every_farm_for_a_round_wins() {
+    let mut state = placement_row(Mode::CaptureAllFarms);
+    state.skip_terrain_placement();
+    place(&mut state, GridPos(0, 0), PlayerTile::Tile, Player::Red, 1);
+    place(&mut state, GridPos(1, 0), PlayerTile::Farm, Player::Red, 1);
+
+    play(&mut state, end_turn(RED));
+    assert_eq!(state.game_phase, GamePhase::Game);
+
+    play(&mut state, end_turn(BLUE));
+    assert_eq!(
+        state.game_phase,
+        GamePhase::Finished {
+            winner: Player::Red,
+            reason: GameOverReason::FarmsHeld
+        }
+    );
+}
+
+#[test]
+fn modes_are_picked_by_their_kebab_case_names() {
+    assert_eq!("king-of-the-hill".parse(), Ok(Mode::KingOfTheHill));
+    assert_eq!("capture-all-farms".parse(), Ok(Mode::CaptureAllFarms));
+    assert_eq!("score-race".parse(), Ok(Mode::ScoreRace { turns: 0 }));
+}
@@ -0,0 +1,73 @@
+use bevy::prelude::KeyCode;
+use store::*;
+
+mod common;
+use common::*;
+
+fn repair(client_id: u64, x: i32) -> TileEvent {
+    input(client_id, GridPos(x, 0), GameInput::Keyboard(KeyCode::R))
+}
+
+/// A one row board with red's base on the left, a level 3 red tile down to
+/// 1 HP next to it and blue's base on the right.
+fn damaged_row() -> GameState {
+    let mut state = row(5);
+    place(&mut state, GridPos(-1, 0), PlayerTile::Tile, Player::Red, 3);
+    state.grid.get_tile_mut(GridPos(-1, 0)).damage(2);
+    state
+}
+
+#[test]
+fn repairs_restore_full_hp_and_are_paid_per_hp() {
+    let mut state = damaged_row();
+    let before = state.economy.balance(Player::Red);
+
+    play(&mut state, repair(RED, -1));
+
+    assert_eq!(
+        state.grid.get_tile(GridPos(-1, 0)),
+        TileType::EMPTY.with_owner(PlayerTile::Tile, Player::Red, 3, 3)
+    );
+    assert_eq!(
+        state.economy.balance(Player::Red),
+        before - 2 * CostTable::DEFAULT.repair
+    );
+    assert_eq!(
+        state.get_action(&repair(RED, -1)),
+        Err(RuleError::NotDamaged)
+    );
+}
+
+#[test]
+fn only_your_own_tiles_can_be_repaired() {
+    let mut state = damaged_row();
+    state.grid.get_tile_mut(GridPos(2, 0)).damage(1);
+
+    assert_eq!(
+        state.get_action(&repair(RED, 2)),
+        Err(RuleError::InvalidInput)
+    );
+    assert_eq!(
+        state.get_action(&repair(RED, -2)),
+        Err(RuleError::NotDamaged)
+    );
+}
+
+#[test]
+fn damaged_bases_regenerate_slowly() {
+    let mut state = damaged_row();
+    state.grid.get_tile_mut(GridPos(-2, 0)).damage(1);
+
+    for _ in 1..state.rules.base_regen_turns {
+        play(&mut state, end_turn(RED));
+        play(&mut state, end_turn(BLUE));
+    }
+    assert_eq!(state.missing_hp(GridPos(-2, 0)), 1);
+
+    let events = play(&mut state, end_turn(RED));
+    assert_eq!(state.missing_hp(GridPos(-2, 0)), 0);
+    assert!(events.contains(&ClientEvent::TileChanges(vec![TileChange {
+        position: GridPos(-2, 0),
+        tile: state.grid.get_tile(GridPos(-2, 0)),
+    }])));
+}
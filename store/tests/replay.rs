@@ -0,0 +1,108 @@
+use bevy::prelude::{KeyCode, MouseButton};
+use store::*;
+
+mod common;
+use common::*;
+
+/// Plays `events` the way the server does, recording every accepted one.
+fn record(state: &mut GameState, events: &[TileEvent]) -> Vec<u8> {
+    let mut log = Vec::new();
+    let mut writer = ReplayWriter::new(&mut log, state).unwrap();
+
+    for (timestamp, event) in events.iter().enumerate() {
+        let player = state.turn;
+        let turn_number = state.turn_number;
+        let Ok(action) = state.get_action(event) else {
+            continue;
+        };
+        let changes = state.consume(&action);
+
+        writer
+            .record(&ReplayEntry {
+                player,
+                turn_number,
+                timestamp: timestamp as u64,
+                event: event.clone(),
+                action: None,
+                changes,
+            })
+            .unwrap();
+    }
+
+    log
+}
+
+fn recorded_game() -> (GameState, Vec<u8>) {
+    let mut state = seated(MapConfig::new(8, 4));
+
+    let left = MouseButton::Left;
+    let right = MouseButton::Right;
+    let log = record(
+        &mut state,
+        &[
+            terrain(RED, GridPos(0, 0), GameInput::Mouse(left)),
+            terrain(RED, GridPos(0, 0), GameInput::Keyboard(KeyCode::Return)),
+            terrain(BLUE, GridPos(1, 0), GameInput::Keyboard(KeyCode::W)),
+            terrain(BLUE, GridPos(1, 0), GameInput::Mouse(left)),
+            terrain(BLUE, GridPos(1, 0), GameInput::Keyboard(KeyCode::Return)),
+            click(RED, GridPos(-3, -2), left),
+            end_turn(RED),
+            // Rejected: not Red's turn any more
+            click(RED, GridPos(-2, -2), left),
+            click(BLUE, GridPos(2, 1), left),
+            end_turn(BLUE),
+            click(RED, GridPos(-3, -2), right),
+            end_turn(RED),
+            click(BLUE, GridPos(2, 1), left),
+            end_turn(BLUE),
+            click(RED, GridPos(-4, -1), left),
+            end_turn(RED),
+            select(BLUE, GridPos(2, 1)),
+            click(BLUE, GridPos(1, 1), left),
+            end_turn(BLUE),
+            click(RED, GridPos(-3, -1), left),
+            end_turn(RED),
+            click(BLUE, GridPos(3, 0), left),
+        ],
+    );
+
+    (state, log)
+}
+
+#[test]
+fn replay_reconstructs_final_grid() {
+    let (live, log) = recorded_game();
+    let replay = Replay::read(log.as_slice()).unwrap();
+
+    assert_eq!(replay.entries.len(), 21);
+
+    let last = replay
+        .apply(replay.initial_state())
+        .last()
+        .expect("replay has entries");
+
+    assert_eq!(last.grid, live.grid);
+    assert_eq!(last.turn, live.turn);
+    assert_eq!(last.turn_number, live.turn_number);
+}
+
+#[test]
+fn replay_matches_recorded_changes() {
+    let (_, log) = recorded_game();
+    let replay = Replay::read(log.as_slice()).unwrap();
+
+    let mut state = replay.initial_state();
+    for entry in &replay.entries {
+        assert_eq!(state.turn, entry.player);
+        assert_eq!(state.turn_number, entry.turn_number);
+
+        let action = state.get_action(&entry.event).unwrap();
+        assert_eq!(state.consume(&action), entry.changes);
+    }
+}
+
+#[test]
+fn replay_rejects_garbage() {
+    assert!(Replay::read("".as_bytes()).is_err());
+    assert!(Replay::read("{\"not\": \"a header\"}".as_bytes()).is_err());
+}
@@ -0,0 +1,102 @@
+use std::path::Path;
+
+use store::*;
+
+mod common;
+use common::*;
+
+fn skirmish() -> RuleSet {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../rules/skirmish.toml");
+    RuleSet::load_from_path(&path).unwrap()
+}
+
+#[test]
+fn rule_files_only_need_what_they_change() {
+    let rules = skirmish();
+    let defaults = RuleSet::default();
+
+    assert_eq!(rules.name, "Skirmish");
+    assert_eq!(rules.starting_resources, 10);
+    assert_eq!(rules.costs.make_tower, 3);
+    assert_eq!(rules.costs.attack, defaults.costs.attack);
+    assert_eq!(rules.terrain_hp.mountain, 3);
+    assert_eq!(rules.terrain_hp.none, defaults.terrain_hp.none);
+    assert_eq!(rules.levels, defaults.levels);
+
+    let json: RuleSet = serde_json::from_str(r#"{"base_hp": 4}"#).unwrap();
+    assert_eq!(
+        json,
+        RuleSet {
+            base_hp: 4,
+            ..RuleSet::default()
+        }
+    );
+}
+
+#[test]
+fn matches_are_played_by_their_rules() {
+    let rules = skirmish();
+    let mut state = seated_with_rules(MapConfig::new(5, 1), rules.clone());
+    state.skip_terrain_placement();
+
+    assert_eq!(
+        state.economy.balance(Player::Red),
+        rules.starting_resources + rules.base_income
+    );
+    assert_eq!(state.action_points, rules.action_points);
+    assert_eq!(
+        state.grid.get_tile(GridPos(-2, 0)).hp(),
+        Some(rules.base_hp)
+    );
+    assert_eq!(
+        state.cost(&GameAction::MakeTower(GridPos(-1, 0))),
+        rules.costs.make_tower
+    );
+    assert!(matches!(
+        &state.sync_events()[0],
+        ClientEvent::Init(_, _, sent) if **sent == rules
+    ));
+}
+
+#[test]
+fn maps_keep_their_own_terrain_budgets() {
+    let map_config = MapConfig {
+        water_budget: Some(1),
+        ..MapConfig::default()
+    };
+    let state = GameState::with_rules(map_config, skirmish());
+
+    assert_eq!(state.rules.water_budget, 1);
+    assert_eq!(
+        state.rules.mountain_budget,
+        RuleSet::default().mountain_budget
+    );
+}
+
+#[test]
+fn rules_tiles_cant_play_by_are_refused() {
+    let mut rules = RuleSet::default();
+    rules.levels.tile[1].attack.clear();
+    assert_eq!(rules.validate(), Err(RuleSetError::NoAttack { level: 2 }));
+
+    rules.levels.tower.clear();
+    assert_eq!(
+        rules.validate(),
+        Err(RuleSetError::NoLevels(PlayerTile::Tower))
+    );
+}
+
+#[test]
+fn rules_without_action_points_are_refused() {
+    let rules = RuleSet {
+        action_points: 0,
+        ..RuleSet::default()
+    };
+    assert_eq!(rules.validate(), Err(RuleSetError::NoActionPoints));
+
+    let rules = RuleSet {
+        farms_per_action_point: 0,
+        ..RuleSet::default()
+    };
+    assert_eq!(rules.validate(), Err(RuleSetError::NoFarmsPerActionPoint));
+}
@@ -0,0 +1,109 @@
+use bevy::prelude::MouseButton;
+use store::*;
+
+mod common;
+use common::*;
+
+/// A 7x3 board with a red tile in the middle.
+fn board(topology: Topology) -> GameState {
+    let mut state = seated(MapConfig {
+        topology,
+        ..MapConfig::new(7, 3)
+    });
+    state.skip_terrain_placement();
+    place(&mut state, GridPos(0, 0), PlayerTile::Tile, Player::Red, 1);
+    state
+}
+
+#[test]
+fn hex_cells_have_six_neighbours_one_step_away() {
+    for position in [GridPos(0, 0), GridPos(3, 1), GridPos(-2, -1)] {
+        let neighbours = Topology::Hex.neighbours(position).collect::<Vec<_>>();
+
+        assert_eq!(neighbours.len(), 6);
+        for &next in &neighbours {
+            assert_eq!(Topology::Hex.distance(position, next), 1);
+            assert!(Topology::Hex.neighbours(next).any(|p| p == position));
+        }
+    }
+}
+
+#[test]
+fn ranges_cover_every_cell_within_reach() {
+    assert_eq!(Topology::Square.within(GridPos(0, 0), 1).count(), 5);
+    assert_eq!(Topology::Hex.within(GridPos(0, 0), 1).count(), 7);
+    assert_eq!(Topology::Hex.within(GridPos(1, 1), 2).count(), 19);
+}
+
+#[test]
+fn hex_lines_follow_the_three_axes() {
+    let topology = Topology::Hex;
+    let origin = GridPos(0, 0);
+
+    let direction = topology.direction(origin, GridPos(1, 2)).unwrap();
+    assert_eq!(
+        topology.line(origin, direction).take(2).collect::<Vec<_>>(),
+        vec![GridPos(0, 1), GridPos(1, 2)]
+    );
+    assert_eq!(topology.direction(origin, GridPos(1, 1)), None);
+    assert_eq!(topology.direction(origin, origin), None);
+}
+
+#[test]
+fn hex_attacks_fan_out_to_the_cells_flanking_the_front() {
+    let topology = Topology::Hex;
+    let origin = GridPos(0, 0);
+    let direction = topology.direction(origin, GridPos(1, 0)).unwrap();
+
+    let front = topology.facing(origin, direction, GridPos(1, 0));
+    let flanks =
+        [GridPos(1, 1), GridPos(1, -1)].map(|offset| topology.facing(origin, direction, offset));
+
+    assert_eq!(front, GridPos(1, 0));
+    for flank in flanks {
+        assert_eq!(topology.distance(origin, flank), 1);
+        assert_eq!(topology.distance(front, flank), 1);
+    }
+    assert_ne!(flanks[0], flanks[1]);
+}
+
+#[test]
+fn diagonal_neighbours_can_only_be_attacked_on_hex_boards() {
+    assert_eq!(
+        board(Topology::Square).get_action(&click(RED, GridPos(-1, 1), MouseButton::Left)),
+        Err(RuleError::TargetNotAdjacent)
+    );
+    assert_eq!(
+        board(Topology::Hex).get_action(&click(RED, GridPos(-1, 1), MouseButton::Left)),
+        Ok(GameAction::Attack(vec![GridPos(-1, 1)]))
+    );
+}
+
+#[test]
+fn hex_world_positions_map_back_to_their_cell() {
+    for y in -3..3 {
+        for x in -3..3 {
+            let position = GridPos(x, y);
+            let centre = Topology::Hex.world_position(position);
+
+            assert_eq!(Topology::Hex.grid_position(centre.0), position);
+        }
+    }
+}
+
+#[test]
+fn cells_are_picked_right_up_to_their_outline() {
+    for topology in [Topology::Square, Topology::Hex] {
+        for y in -3..3 {
+            for x in -3..3 {
+                let position = GridPos(x, y);
+                let centre = topology.world_position(position).0;
+
+                for corner in topology.outline() {
+                    let inside = centre + corner * 0.95;
+                    assert_eq!(topology.grid_position(inside), position, "{}", topology);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use bevy::prelude::{KeyCode, MouseButton};
+use store::*;
+
+mod common;
+use common::*;
+
+/// A five wide row with a red tile next to red's base, facing two of
+/// blue's.
+fn face_off() -> GameState {
+    let mut state = row(5);
+    place(&mut state, GridPos(-1, 0), PlayerTile::Tile, Player::Red, 1);
+    place(&mut state, GridPos(0, 0), PlayerTile::Tile, Player::Blue, 1);
+    place(&mut state, GridPos(1, 0), PlayerTile::Tile, Player::Blue, 1);
+    state
+}
+
+#[test]
+fn towers_are_paid_for() {
+    let mut state = face_off();
+    let before = state.economy.balance(Player::Red);
+
+    play(
+        &mut state,
+        input(RED, GridPos(-1, 0), GameInput::Keyboard(KeyCode::T)),
+    );
+    play(&mut state, end_turn(RED));
+    assert!(state.grid.get_tile(GridPos(-1, 0)).is_tower());
+    assert_eq!(
+        state.economy.balance(Player::Red),
+        before - CostTable::DEFAULT.make_tower
+    );
+
+    play(&mut state, click(BLUE, GridPos(1, 0), MouseButton::Right));
+    play(&mut state, end_turn(BLUE));
+    state.economy.balance[Player::Red as usize] = 0;
+    assert_eq!(
+        state.get_action(&click(RED, GridPos(-1, 0), MouseButton::Left)),
+        Err(RuleError::NotEnoughResources {
+            needed: LevelTable::default().tower[1].upgrade_cost,
+            available: 0,
+        })
+    );
+}
+
+#[test]
+fn towers_fire_at_the_end_of_the_opponents_turn() {
+    let mut state = face_off();
+
+    play(
+        &mut state,
+        input(RED, GridPos(-1, 0), GameInput::Keyboard(KeyCode::T)),
+    );
+    play(&mut state, end_turn(RED));
+    play(&mut state, click(BLUE, GridPos(1, 0), MouseButton::Right));
+    assert_eq!(
+        state.grid.get_tile(GridPos(0, 0)).owner(),
+        Some(Player::Blue)
+    );
+
+    let events = play(&mut state, end_turn(BLUE));
+    assert_eq!(state.grid.get_tile(GridPos(0, 0)), TileType::EMPTY);
+    assert!(state.grid.get_tile(GridPos(1, 0)).is_farm());
+    assert!(events.iter().any(|event| matches!(
+        event,
+        ClientEvent::TileChanges(changes)
+            if changes.contains(&TileChange { position: GridPos(0, 0), tile: TileType::EMPTY })
+    )));
+}
+
+#[test]
+fn towers_leave_bases_alone() {
+    let mut state = row(3);
+    place(&mut state, GridPos(0, 0), PlayerTile::Tile, Player::Red, 1);
+
+    play(
+        &mut state,
+        input(RED, GridPos(0, 0), GameInput::Keyboard(KeyCode::T)),
+    );
+    play(&mut state, end_turn(RED));
+    play(&mut state, click(BLUE, GridPos(0, 0), MouseButton::Left));
+    play(&mut state, end_turn(BLUE));
+
+    assert!(state.grid.get_tile(GridPos(1, 0)).is_base(Player::Blue));
+    assert_eq!(state.winner(), None);
+    assert_eq!(state.turn, Player::Red);
+}
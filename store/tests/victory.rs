@@ -0,0 +1,64 @@
+use bevy::prelude::MouseButton;
+use store::*;
+
+mod common;
+use common::*;
+
+fn game_over(reason: GameOverReason) -> ClientEvent {
+    ClientEvent::GameOver {
+        winner: Player::Red,
+        reason,
+    }
+}
+
+#[test]
+fn taking_a_base_with_tiles_around_it_destroys_it() {
+    // Red holds the top row up to blue's base, blue the tile left of it
+    let mut state = seated(MapConfig::new(5, 2));
+    state.skip_terrain_placement();
+    for x in -1..=2 {
+        place(&mut state, GridPos(x, -1), PlayerTile::Tile, Player::Red, 1);
+    }
+    place(&mut state, GridPos(1, 0), PlayerTile::Tile, Player::Blue, 1);
+    state.grid.get_tile_mut(GridPos(2, 0)).damage(1);
+
+    let events = play(&mut state, click(RED, GridPos(2, 0), MouseButton::Left));
+
+    assert!(events.contains(&game_over(GameOverReason::BaseDestroyed)));
+    assert_eq!(
+        state.game_phase,
+        GamePhase::Finished {
+            winner: Player::Red,
+            reason: GameOverReason::BaseDestroyed
+        }
+    );
+    assert_eq!(state.grid.get_tile(GridPos(1, 0)), TileType::EMPTY);
+}
+
+#[test]
+fn taking_a_bare_base_leaves_no_tiles() {
+    let mut state = row(3);
+    place(&mut state, GridPos(0, 0), PlayerTile::Tile, Player::Red, 1);
+    state.grid.get_tile_mut(GridPos(1, 0)).damage(1);
+
+    let events = play(&mut state, click(RED, GridPos(1, 0), MouseButton::Left));
+
+    assert!(events.contains(&game_over(GameOverReason::NoTilesLeft)));
+    assert_eq!(state.winner(), Some(Player::Red));
+}
+
+#[test]
+fn nothing_is_accepted_after_the_game_is_over() {
+    let mut state = row(3);
+    place(&mut state, GridPos(0, 0), PlayerTile::Tile, Player::Red, 1);
+    state.grid.get_tile_mut(GridPos(1, 0)).damage(1);
+    play(&mut state, click(RED, GridPos(1, 0), MouseButton::Left));
+
+    for event in [
+        click(RED, GridPos(0, 0), MouseButton::Right),
+        end_turn(RED),
+        end_turn(BLUE),
+    ] {
+        assert_eq!(state.get_action(&event), Err(RuleError::GameOver));
+    }
+}